@@ -0,0 +1,401 @@
+//! Exact odds behind dice and tables - no Monte Carlo sampling involved.
+//!
+//! # `Distribution`
+//!
+//! Numeric outcome distribution, e.g. of a [DiceExpr]:
+//!
+//! ```
+//! use rpgassist::dice::DiceExpr;
+//!
+//! let d = "3d6".parse::<DiceExpr>().unwrap().distribution().unwrap();
+//! assert!((d.mean() - 10.5).abs() < 1e-9);
+//! assert!((d.at_least(11) - 0.5).abs() < 1e-9);
+//! ```
+//!
+//! # `Odds`
+//!
+//! Categorical outcome distribution, e.g. of a [RollTable][crate::table::RollTable]
+//! or [biased gender][crate::gender::Gender::odds_biased].
+//!
+//! Exploding dice would go on forever, so rolling stops after
+//! [MAX_EXPLOSIONS] explosions - and so do the odds. A die which hits max
+//! every time simply ends at `(MAX_EXPLOSIONS + 1) × sides`, which keeps the
+//! odds exact for the dice as they are actually rolled.
+//!
+//! Exact odds of big dice pools get costly fast, keep rules especially - so
+//! there's a [limit][MAX_EXACT_WORK] to how much work working them out may take.
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::dice::{DiceExpr, DiceTerm, Keep, MAX_EXPLOSIONS, Term};
+
+/// Rough cap on the work an exact [Distribution] of a single dice term may take,
+/// counted in die outcomes folded in. A pool of `n` dice with `v` possible values
+/// each costs about `n² × v² / 2` summed up plainly, and `n × v × c` with a keep
+/// rule, `c` being the number of combinations the kept dice can be in.
+///
+/// E.g. `4d6kh3` takes a few thousand, `100d20` a couple of million, while
+/// `100d100kh50` is way (*way*) over.
+pub const MAX_EXACT_WORK: u64 = 10_000_000;
+
+/// Failure to work out a [Distribution].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DistributionError {
+    /// A dice term would take more than [MAX_EXACT_WORK] to work out exactly.
+    TooComplex(String),
+}
+
+impl Display for DistributionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooComplex(term) => write!(f, "Exact odds of '{term}' are too costly to work out"),
+        }
+    }
+}
+
+impl std::error::Error for DistributionError {}
+
+/// Probability distribution of numeric outcomes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution {
+    outcomes: BTreeMap<i32, f64>,
+}
+
+impl Distribution {
+    /// A "distribution" of a single sure outcome.
+    pub fn constant(value: i32) -> Self {
+        Self { outcomes: BTreeMap::from([(value, 1.0)]) }
+    }
+
+    /// Distribution of a single `sides`-sided die.
+    pub fn die(sides: u32) -> Self {
+        let p = 1.0 / sides as f64;
+        Self { outcomes: (1..=sides as i32).map(|v| (v, p)).collect() }
+    }
+
+    /// Distribution of a single exploding `sides`-sided die.
+    pub fn exploding_die(sides: u32) -> Self {
+        let p = 1.0 / sides as f64;
+        let mut outcomes = BTreeMap::new();
+        let mut carry = 1.0;// probability of having exploded this far
+        for depth in 0..=MAX_EXPLOSIONS {
+            let base = (depth * sides) as i32;
+            for v in 1..sides as i32 {
+                *outcomes.entry(base + v).or_insert(0.0) += carry * p;
+            }
+            carry *= p;
+        }
+        // maxed out on every roll, i.e. ran out of explosions
+        *outcomes.entry(((MAX_EXPLOSIONS + 1) * sides) as i32).or_insert(0.0) += carry;
+        Self { outcomes }
+    }
+
+    /// Sum of two independent distributions, saturating at the `i32` limits
+    /// like [DiceRoll::total][crate::dice::DiceRoll::total].
+    pub fn add(&self, other: &Distribution) -> Self {
+        let mut outcomes = BTreeMap::new();
+        for (a, pa) in &self.outcomes {
+            for (b, pb) in &other.outcomes {
+                *outcomes.entry(a.saturating_add(*b)).or_insert(0.0) += pa * pb;
+            }
+        }
+        Self { outcomes }
+    }
+
+    /// Negated distribution.
+    pub fn negate(&self) -> Self {
+        Self { outcomes: self.outcomes.iter().map(|(v, p)| (v.saturating_neg(), *p)).collect() }
+    }
+
+    /// Iterate through the outcomes and their probabilities, lowest outcome first.
+    pub fn outcomes(&self) -> impl Iterator<Item = (i32, f64)> + '_ {
+        self.outcomes.iter().map(|(v, p)| (*v, *p))
+    }
+
+    /// Lowest possible outcome.
+    pub fn min(&self) -> i32 {
+        *self.outcomes.keys().next().expect("Empty distribution?!")
+    }
+
+    /// Highest possible outcome.
+    pub fn max(&self) -> i32 {
+        *self.outcomes.keys().next_back().expect("Empty distribution?!")
+    }
+
+    /// P(X = `value`).
+    pub fn probability(&self, value: i32) -> f64 {
+        self.outcomes.get(&value).copied().unwrap_or(0.0)
+    }
+
+    /// P(X ≥ `value`).
+    pub fn at_least(&self, value: i32) -> f64 {
+        self.outcomes.range(value..).map(|(_, p)| p).sum()
+    }
+
+    /// P(X ≤ `value`).
+    pub fn at_most(&self, value: i32) -> f64 {
+        self.outcomes.range(..=value).map(|(_, p)| p).sum()
+    }
+
+    /// Expected value.
+    pub fn mean(&self) -> f64 {
+        self.outcomes.iter().map(|(v, p)| *v as f64 * p).sum()
+    }
+
+    /// Variance.
+    pub fn variance(&self) -> f64 {
+        let mean = self.mean();
+        self.outcomes.iter().map(|(v, p)| (*v as f64 - mean).powi(2) * p).sum()
+    }
+
+    /// Standard deviation.
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// The lowest outcome `x` with P(X ≤ `x`) ≥ `percent`/100.
+    pub fn percentile(&self, percent: f64) -> i32 {
+        let target = (percent / 100.0).clamp(0.0, 1.0);
+        let mut cumulative = 0.0;
+        for (v, p) in &self.outcomes {
+            cumulative += p;
+            // a smidge of slack for floating point rounding…
+            if cumulative >= target - 1e-12 {
+                return *v
+            }
+        }
+        self.max()
+    }
+
+    /// Median, i.e. the 50th [percentile][Distribution::percentile].
+    pub fn median(&self) -> i32 {
+        self.percentile(50.0)
+    }
+}
+
+/// Get the number of ways to pick `n` values out of `v`, repetition allowed -
+/// or anything over `limit`.
+fn multisets(n: u64, v: u64, limit: u64) -> u64 {
+    let mut c: u128 = 1;
+    for i in 1..=n as u128 {
+        c = c * (v as u128 - 1 + i) / i;
+        if c > limit as u128 {
+            return limit.saturating_add(1)
+        }
+    }
+    c as u64
+}
+
+impl TryFrom<&DiceTerm> for Distribution {
+    type Error = DistributionError;
+
+    fn try_from(term: &DiceTerm) -> Result<Self, Self::Error> {
+        let die = if term.explode {
+            Distribution::exploding_die(term.dice.sides)
+        } else {
+            Distribution::die(term.dice.sides)
+        };
+        let (count, values) = (term.dice.count as u64, die.outcomes.len() as u64);
+        let work = match term.keep {
+            None => (count * values).saturating_pow(2) / 2,
+            Some(Keep::Highest(n) | Keep::Lowest(n)) => {
+                let c = multisets((n as u64).min(count), values, MAX_EXACT_WORK);
+                (count * values).saturating_mul(c)
+            },
+        };
+        if work > MAX_EXACT_WORK {
+            return Err(DistributionError::TooComplex(term.to_string()))
+        }
+        let Some(keep) = term.keep else {
+            return Ok((0..term.dice.count).fold(Distribution::constant(0), |acc, _| acc.add(&die)))
+        };
+
+        // Track the kept dice (sorted) as the state, folding in one die at a time.
+        let (n, highest) = match keep {
+            Keep::Highest(n) => (n as usize, true),
+            Keep::Lowest(n) => (n as usize, false),
+        };
+        let mut states: BTreeMap<Vec<i32>, f64> = BTreeMap::from([(vec![], 1.0)]);
+        for _ in 0..term.dice.count {
+            let mut next = BTreeMap::new();
+            for (kept, p) in &states {
+                for (v, pv) in die.outcomes() {
+                    let mut k = kept.clone();
+                    let at = k.partition_point(|x| *x < v);
+                    k.insert(at, v);
+                    if k.len() > n {
+                        if highest { k.remove(0); } else { k.pop(); }
+                    }
+                    *next.entry(k).or_insert(0.0) += p * pv;
+                }
+            }
+            states = next;
+        }
+        let mut outcomes = BTreeMap::new();
+        for (kept, p) in states {
+            *outcomes.entry(kept.iter().fold(0i32, |t, v| t.saturating_add(*v))).or_insert(0.0) += p;
+        }
+        Ok(Self { outcomes })
+    }
+}
+
+impl TryFrom<&DiceExpr> for Distribution {
+    type Error = DistributionError;
+
+    fn try_from(expr: &DiceExpr) -> Result<Self, Self::Error> {
+        expr.terms().iter().try_fold(Distribution::constant(0), |acc, (negative, t)| {
+            let d = match t {
+                Term::Const(v) => Distribution::constant(*v),
+                Term::Dice(d) => Distribution::try_from(d)?,
+            };
+            Ok(acc.add(&if *negative { d.negate() } else { d }))
+        })
+    }
+}
+
+/// Probability distribution of categorical outcomes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Odds<T> {
+    outcomes: Vec<(T, f64)>,
+}
+
+impl<T: PartialEq> Odds<T> {
+    /// Make empty odds.
+    pub fn new() -> Self {
+        Self { outcomes: vec![] }
+    }
+
+    /// Add `p` to the probability of `outcome`.
+    pub fn add(&mut self, outcome: T, p: f64) {
+        match self.outcomes.iter_mut().find(|(o, _)| *o == outcome) {
+            Some((_, q)) => *q += p,
+            None => self.outcomes.push((outcome, p)),
+        }
+    }
+
+    /// Scale all the probabilities by `factor`.
+    pub fn scale(&mut self, factor: f64) {
+        self.outcomes.iter_mut().for_each(|(_, p)| *p *= factor);
+    }
+
+    /// Iterate through the outcomes and their probabilities.
+    pub fn outcomes(&self) -> impl Iterator<Item = (&T, f64)> {
+        self.outcomes.iter().map(|(o, p)| (o, *p))
+    }
+
+    /// P(`outcome`).
+    pub fn probability(&self, outcome: &T) -> f64 {
+        self.outcomes.iter().find(|(o, _)| o == outcome).map(|(_, p)| *p).unwrap_or(0.0)
+    }
+
+    /// P(any outcome matching `pred`).
+    pub fn probability_of<F: Fn(&T) -> bool>(&self, pred: F) -> f64 {
+        self.outcomes.iter().filter(|(o, _)| pred(o)).map(|(_, p)| p).sum()
+    }
+}
+
+impl<T: PartialEq> Default for Odds<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod analysis_tests {
+    use crate::{body::location::BodyLocation, direction::bilateral::Bilateral, gender::{Gender, GenderBias}};
+
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn dist(s: &str) -> Distribution {
+        s.parse::<DiceExpr>().unwrap().distribution().unwrap()
+    }
+
+    #[test]
+    fn big_pools_are_refused() {
+        let too_big = "100d100kh50".parse::<DiceExpr>().unwrap().distribution();
+        assert_eq!(Err(DistributionError::TooComplex("100d100kh50".into())), too_big);
+        assert!("1000d10000".parse::<DiceExpr>().unwrap().distribution().is_err());
+        assert!((dist("100d20").mean() - 1050.0).abs() < 1e-6);
+        assert!("100d100".parse::<DiceExpr>().unwrap().distribution().is_err());
+        assert_eq!((5, 50), (dist("10d10kh5").min(), dist("10d10kh5").max()));
+    }
+
+    #[test]
+    fn three_d6() {
+        let d = dist("3d6");
+        assert_eq!((3, 18), (d.min(), d.max()));
+        assert!((d.mean() - 10.5).abs() < EPSILON);
+        assert!((d.variance() - 8.75).abs() < EPSILON);
+        assert!((d.probability(3) - 1.0 / 216.0).abs() < EPSILON);
+        assert!((d.at_least(11) - 0.5).abs() < EPSILON);
+        assert_eq!(10, d.median());
+    }
+
+    #[test]
+    fn four_d6_keep_highest_three() {
+        let d = dist("4d6kh3");
+        assert_eq!((3, 18), (d.min(), d.max()));
+        // well known 15869/1296 …
+        assert!((d.mean() - 15869.0 / 1296.0).abs() < EPSILON);
+        assert!((d.probability(18) - 21.0 / 1296.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn keep_lowest() {
+        let d = dist("2d20kl1");
+        assert!((d.probability(20) - 1.0 / 400.0).abs() < EPSILON);
+        assert!((d.probability(1) - 39.0 / 400.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn subtraction_and_constants() {
+        let d = dist("2d10-1d4+1");
+        assert_eq!((-1, 20), (d.min(), d.max()));
+        assert!((d.mean() - 9.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn exploding_dice() {
+        let d = dist("1d6!");
+        let total: f64 = d.outcomes().map(|(_, p)| p).sum();
+        assert!((total - 1.0).abs() < EPSILON);
+        assert_eq!(0.0, d.probability(6));
+        assert!((d.probability(7) - 1.0 / 36.0).abs() < EPSILON);
+        assert!((d.mean() - 4.2).abs() < 1e-6);
+        // rolls stop at max on every one of the MAX_EXPLOSIONS + 1 rolls
+        let top = 6 * (MAX_EXPLOSIONS as i32 + 1);
+        assert_eq!(top, d.max());
+        assert!((d.probability(top) - (1.0 / 6.0f64).powi(MAX_EXPLOSIONS as i32 + 1)).abs() < EPSILON);
+    }
+
+    #[test]
+    fn percentiles() {
+        let d = dist("1d100");
+        assert_eq!(1, d.percentile(0.0));
+        assert_eq!(25, d.percentile(25.0));
+        assert_eq!(100, d.percentile(100.0));
+    }
+
+    #[test]
+    fn gender_two_thirds_biases() {
+        // d20 -3/+3 isn't quite ⅔, and the two aren't quite symmetric either
+        let odds = Gender::odds_biased(GenderBias::Male23);
+        assert!((odds.probability(&Gender::Male) - 13.0 / 20.0).abs() < EPSILON);
+        let odds = Gender::odds_biased(GenderBias::Female23);
+        assert!((odds.probability(&Gender::Female) - 14.0 / 20.0).abs() < EPSILON);
+        let odds = Gender::odds_biased(GenderBias::None);
+        assert!((odds.probability(&Gender::Male) - 0.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn t867_odds() {
+        let odds = BodyLocation::table().odds();
+        let total: f64 = odds.outcomes().map(|(_, p)| p).sum();
+        assert!((total - 1.0).abs() < EPSILON);
+        assert!((odds.probability(&BodyLocation::Chest) - 0.2).abs() < EPSILON);
+        assert!((odds.probability(&BodyLocation::Genitals) - 1.0 / 60.0).abs() < EPSILON);
+        assert!((odds.probability(&BodyLocation::Buttocks) - (1.0 / 20.0 + 2.0 / 60.0)).abs() < EPSILON);
+        assert!((odds.probability(&BodyLocation::Arm(Bilateral::Left)) - 0.05).abs() < EPSILON);
+    }
+}
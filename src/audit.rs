@@ -0,0 +1,172 @@
+//! Opt-in audit log of random decisions.
+//!
+//! Wrap any [DiceRng] into an [AuditedRng] and hand that to the `*_with(rng)`
+//! generators - every random decision they make gets recorded as a
+//! [RollRecord]: where it came from, what dice were rolled, the raw result(s)
+//! and what it all was interpreted as.
+//!
+//! ```
+//! use rpgassist::{audit::AuditedRng, body::location::BodyLocation, gender::Gender, rng::SeededRng};
+//!
+//! let mut rng = AuditedRng::new(SeededRng::new(867));
+//! let _ = Gender::random_with(&mut rng);
+//! let _ = BodyLocation::random_with(&mut rng);
+//! assert!(rng.records().len() >= 2);
+//! println!("{}", rng.to_json().unwrap());
+//! ```
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rng::DiceRng;
+
+/// A single recorded roll.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RollRecord {
+    /// Function or table the roll originated from, e.g. `"T867"`.
+    pub source: String,
+    /// The dice rolled, e.g. `"1d20"`.
+    pub dice: String,
+    /// Raw die face(s).
+    pub raw: Vec<u32>,
+    /// What the roll was interpreted as.
+    pub outcome: String,
+}
+
+impl RollRecord {
+    /// Make a new record.
+    pub fn new<S: Display, D: Display, O: Display>(source: S, dice: D, raw: Vec<u32>, outcome: O) -> Self {
+        Self {
+            source: source.to_string(),
+            dice: dice.to_string(),
+            raw,
+            outcome: outcome.to_string(),
+        }
+    }
+}
+
+impl Display for RollRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let raw: Vec<String> = self.raw.iter().map(|r| r.to_string()).collect();
+        write!(f, "{}: {} [{}] → {}", self.source, self.dice, raw.join(", "), self.outcome)
+    }
+}
+
+/// A [DiceRng] wrapper which keeps a log of [RollRecord]s.
+#[derive(Debug, Clone)]
+pub struct AuditedRng<R: DiceRng> {
+    inner: R,
+    records: Vec<RollRecord>,
+}
+
+impl<R: DiceRng> AuditedRng<R> {
+    /// Wrap the given `rng`.
+    pub fn new(rng: R) -> Self {
+        Self { inner: rng, records: vec![] }
+    }
+
+    /// Get the records so far.
+    pub fn records(&self) -> &[RollRecord] {
+        &self.records
+    }
+
+    /// Forget the records so far.
+    pub fn clear(&mut self) {
+        self.records.clear()
+    }
+
+    /// Unwrap into the inner RNG and the records.
+    pub fn into_parts(self) -> (R, Vec<RollRecord>) {
+        (self.inner, self.records)
+    }
+
+    /// Export the records as (pretty) JSON.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.records)
+    }
+}
+
+impl<R: DiceRng> DiceRng for AuditedRng<R> {
+    fn roll_die(&mut self, sides: u32) -> u32 {
+        self.inner.roll_die(sides)
+    }
+
+    fn is_recording(&self) -> bool {
+        true
+    }
+
+    fn record(&mut self, record: RollRecord) {
+        log::debug!("{record}");
+        self.records.push(record)
+    }
+}
+
+#[cfg(test)]
+mod audit_tests {
+    use crate::{body::location::BodyLocation, direction::bilateral::Bilateral, gender::{Gender, GenderBias}, rng::SeededRng};
+
+    use super::*;
+
+    #[test]
+    fn gender_gets_recorded() {
+        let mut rng = AuditedRng::new(SeededRng::new(1));
+        let g = Gender::random_biased_with(GenderBias::Male23, &mut rng);
+        let rec = &rng.records()[0];
+        assert_eq!("Gender::random_biased", rec.source);
+        assert_eq!("1d20-3", rec.dice);
+        assert_eq!(1, rec.raw.len());
+        assert_eq!(format!("{g:?}"), rec.outcome);
+    }
+
+    #[test]
+    fn bilateral_gets_recorded() {
+        let mut rng = AuditedRng::new(SeededRng::new(2));
+        let b = Bilateral::random_lr_with(&mut rng);
+        let rec = &rng.records()[0];
+        assert_eq!("1d2", rec.dice);
+        assert_eq!(b.to_string(), rec.outcome);
+    }
+
+    #[test]
+    fn table_rolls_get_recorded() {
+        let mut rng = AuditedRng::new(SeededRng::new(3));
+        for _ in 0..200 {
+            let loc = BodyLocation::random_with(&mut rng);
+            let rec = rng.records().last().unwrap();
+            assert!(rec.source == "T867" || rec.source == "T8678");
+            assert_eq!(loc.to_string(), rec.outcome);
+        }
+        // roll 8 goes to the groin sub-table, which records as a table of its own
+        let subs = rng.records().iter().filter(|r| r.source == "T8678").count();
+        assert!(subs > 0);
+        // …and every roll into it is followed by its record
+        let recs = rng.records();
+        let into_subs: Vec<usize> = (0..recs.len())
+            .filter(|i| recs[*i].source == "T867" && recs[*i].outcome.ends_with("see T8678"))
+            .collect();
+        assert_eq!(subs, into_subs.len());
+        assert!(into_subs.iter().all(|i| recs[i + 1].source == "T8678"));
+        assert_eq!(200 + subs, rng.records().len());
+    }
+
+    #[test]
+    fn same_seed_same_log() {
+        let log = |seed| {
+            let mut rng = AuditedRng::new(SeededRng::new(seed));
+            for _ in 0..10 {
+                let _ = BodyLocation::random_with(&mut rng);
+            }
+            rng.to_json().unwrap()
+        };
+        assert_eq!(log(4), log(4));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut rng = AuditedRng::new(SeededRng::new(5));
+        let _ = Gender::random_with(&mut rng);
+        let json = rng.to_json().unwrap();
+        let records: Vec<RollRecord> = serde_json::from_str(&json).unwrap();
+        assert_eq!(rng.records(), records.as_slice());
+    }
+}
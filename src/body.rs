@@ -1,2 +1,7 @@
 //! Body (humanoid or otherwise) related stuff.
-pub mod location;
\ No newline at end of file
+pub mod anatomy;
+pub mod armor;
+pub mod called_shot;
+pub mod location;
+pub mod plan;
+pub mod wound;
\ No newline at end of file
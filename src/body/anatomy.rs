@@ -0,0 +1,334 @@
+//! Per-creature anatomy state - which [body locations][BodyLocation] are still there,
+//! and in what shape.
+//!
+//! Losing a part takes whatever hangs off it along, e.g. a lost
+//! [hand][BodyLocation::Hand] means no [fingers][BodyLocation::Fingers] or
+//! [thumb][BodyLocation::Thumb] on that side either.
+//!
+//! ```
+//! use rpgassist::{body::{anatomy::{Anatomy, PartState}, location::BodyLocation}, direction::bilateral::Bilateral, stat::StatBase};
+//!
+//! let mut body = Anatomy::new();
+//! body.lose_fingers(Bilateral::Left, 2);
+//! assert_eq!(2, body.fingers(Bilateral::Left));
+//! body.set(BodyLocation::Hand(Bilateral::Left), PartState::Missing);
+//! assert_eq!(0, body.fingers(Bilateral::Left));
+//! assert!(body.penalty(&StatBase::Dex) < 0);
+//! ```
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{body::location::BodyLocation, direction::bilateral::Bilateral, rng::{ChaosRng, DiceRng}, stat::StatBase};
+
+/// How many fingers (thumb excluded) a hand has to begin with.
+pub const FINGERS_PER_HAND: u8 = 4;
+
+/// State of a body part.
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartState {
+    #[default]
+    Intact,
+    /// Still there, but not usable (e.g. crippled).
+    Disabled,
+    Missing,
+    /// Replaced by an artificial part.
+    Prosthetic,
+}
+
+impl PartState {
+    /// Is the part (or its replacement) there at all?
+    pub fn is_present(&self) -> bool {
+        !matches!(self, Self::Missing)
+    }
+}
+
+impl Display for PartState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Intact => "intact",
+            Self::Disabled => "disabled",
+            Self::Missing => "missing",
+            Self::Prosthetic => "prosthetic",
+        })
+    }
+}
+
+/// A non-[intact][PartState::Intact] part.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct PartEntry {
+    pub location: BodyLocation,
+    pub state: PartState,
+}
+
+/// Anatomy state of a single creature.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Anatomy {
+    /// Parts which aren't intact.
+    #[serde(default)]
+    parts: Vec<PartEntry>,
+    /// Fingers left on the (left, right) hand.
+    #[serde(deserialize_with = "de_fingers")]
+    fingers: (u8, u8),
+}
+
+/// Deserialize finger counts, rejecting more than [FINGERS_PER_HAND] a hand.
+fn de_fingers<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<(u8, u8), D::Error> {
+    let (left, right) = <(u8, u8)>::deserialize(deserializer)?;
+    if left.max(right) > FINGERS_PER_HAND {
+        return Err(serde::de::Error::custom(format!("a hand has at most {FINGERS_PER_HAND} fingers")))
+    }
+    Ok((left, right))
+}
+
+impl Default for Anatomy {
+    fn default() -> Self {
+        Self { parts: vec![], fingers: (FINGERS_PER_HAND, FINGERS_PER_HAND) }
+    }
+}
+
+/// Get the location `loc` is attached to, if it matters for losing parts.
+fn attached_to(loc: &BodyLocation) -> Option<BodyLocation> {
+    match loc {
+        BodyLocation::Fingers { side, .. } |
+        BodyLocation::Thumb(side) => Some(BodyLocation::Hand(*side)),
+        BodyLocation::Hand(side) => Some(BodyLocation::Arm(*side)),
+        BodyLocation::Foot(side) => Some(BodyLocation::Leg(*side)),
+        _ => None
+    }
+}
+
+impl Anatomy {
+    /// A creature with everything intact.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get own state of `loc`, ignoring what it's attached to.
+    fn own_state(&self, loc: &BodyLocation) -> PartState {
+        self.parts.iter()
+            .find(|p| p.location.same_spot(loc))
+            .map(|p| p.state)
+            .unwrap_or_default()
+    }
+
+    /// Get state of `loc`.
+    ///
+    /// A part attached to a missing one is missing too. [Fingers][BodyLocation::Fingers]
+    /// count as missing only when none are left.
+    pub fn state(&self, loc: &BodyLocation) -> PartState {
+        if let Some(parent) = attached_to(loc) && !self.state(&parent).is_present() {
+            return PartState::Missing
+        }
+        if let BodyLocation::Fingers { side, .. } = loc && self.finger_count(*side) == 0 {
+            return PartState::Missing
+        }
+        self.own_state(loc)
+    }
+
+    /// Set state of `loc`.
+    ///
+    /// Setting [fingers][BodyLocation::Fingers] [missing][PartState::Missing]
+    /// [loses][Anatomy::lose_fingers] `count` of them; otherwise the state applies to
+    /// all the fingers of that hand.
+    pub fn set(&mut self, loc: BodyLocation, state: PartState) {
+        if let (BodyLocation::Fingers { count, side }, PartState::Missing) = (&loc, state) {
+            self.lose_fingers(*side, *count);
+            return
+        }
+        self.parts.retain(|p| !p.location.same_spot(&loc));
+        if state != PartState::Intact {
+            self.parts.push(PartEntry { location: loc, state });
+        }
+    }
+
+    /// Lose the part at `loc` (and whatever is attached to it).
+    pub fn lose(&mut self, loc: BodyLocation) {
+        self.set(loc, PartState::Missing)
+    }
+
+    /// Lose `count` fingers from the hand on the given `side`.
+    ///
+    /// Hands are either left or right - other sides have no fingers to lose.
+    pub fn lose_fingers(&mut self, side: Bilateral, count: u8) {
+        if let Some(f) = self.finger_count_mut(side) {
+            *f = f.saturating_sub(count);
+        }
+    }
+
+    /// Get fingers left on the `side` hand, zero if there's no such hand.
+    fn finger_count(&self, side: Bilateral) -> u8 {
+        match side {
+            Bilateral::Left => self.fingers.0,
+            Bilateral::Right => self.fingers.1,
+            _ => 0
+        }
+    }
+
+    fn finger_count_mut(&mut self, side: Bilateral) -> Option<&mut u8> {
+        match side {
+            Bilateral::Left => Some(&mut self.fingers.0),
+            Bilateral::Right => Some(&mut self.fingers.1),
+            _ => None
+        }
+    }
+
+    /// Is the part at `loc` there at all (maybe as a [prosthetic][PartState::Prosthetic])?
+    pub fn is_present(&self, loc: &BodyLocation) -> bool {
+        self.state(loc).is_present()
+    }
+
+    /// Number of fingers (thumb excluded) left on the hand on the given `side`;
+    /// zero if `side` is neither left nor right.
+    pub fn fingers(&self, side: Bilateral) -> u8 {
+        if self.is_present(&BodyLocation::Hand(side)) {
+            self.finger_count(side)
+        } else {
+            0
+        }
+    }
+
+    /// Number of eyes left (disabled and prosthetic ones included).
+    pub fn eyes(&self) -> u8 {
+        [Bilateral::Left, Bilateral::Right].iter()
+            .filter(|s| self.is_present(&BodyLocation::Eye(**s)))
+            .count() as u8
+    }
+
+    /// Iterate through the parts which aren't intact.
+    pub fn damaged(&self) -> impl Iterator<Item = &PartEntry> {
+        self.parts.iter()
+    }
+
+    /// Generate a random hit location, skipping missing parts.
+    pub fn random_location(&self) -> BodyLocation {
+        self.random_location_with(&mut ChaosRng)
+    }
+
+    /// Generate a random hit location, skipping missing parts, using the given `rng`.
+    ///
+    /// # Panic
+    /// If there's nothing left to hit on [T867][BodyLocation::table] (which
+    /// would be quite a feat).
+    pub fn random_location_with<R: DiceRng + ?Sized>(&self, rng: &mut R) -> BodyLocation {
+        BodyLocation::table()
+            .retain(|l| self.is_present(l))
+            .expect("Nothing left to hit?!")
+            .roll_recorded_with(rng)
+    }
+
+    /// Get the total penalty to the given stat caused by lost, disabled and replaced parts.
+    ///
+    /// * **Dex**: hand -3 missing, -2 disabled, -1 prosthetic; -1 per lost finger,
+    ///   -2 per lost thumb; leg/foot -2 missing, -1 disabled or prosthetic; -1 per lost eye.
+    /// * **Str**: arm -2 missing, -1 disabled or prosthetic.
+    /// * **App**: -1 per lost eye (a prosthetic one looks good enough).
+    pub fn penalty(&self, stat: &StatBase) -> i32 {
+        let sides = [Bilateral::Left, Bilateral::Right];
+        let eyes_lost = sides.iter().filter(|s| !self.is_present(&BodyLocation::Eye(**s))).count() as i32;
+        match stat {
+            StatBase::Dex => sides.iter().map(|s| {
+                let hand = match self.state(&BodyLocation::Hand(*s)) {
+                    PartState::Missing => -3,
+                    PartState::Disabled => -2,
+                    PartState::Prosthetic => -1,
+                    PartState::Intact => {
+                        let thumb = if self.is_present(&BodyLocation::Thumb(*s)) { 0 } else { -2 };
+                        thumb - (FINGERS_PER_HAND as i32 - self.fingers(*s) as i32)
+                    },
+                };
+                let legs: i32 = [BodyLocation::Leg(*s), BodyLocation::Foot(*s)].iter().map(|l| match self.own_state(l) {
+                    _ if !self.is_present(l) => -2,
+                    PartState::Disabled | PartState::Prosthetic => -1,
+                    _ => 0
+                }).sum();
+                // a lost leg takes the foot along, but penalize it only once…
+                let legs = legs.max(-2);
+                hand + legs
+            }).sum::<i32>() - eyes_lost,
+            StatBase::Str => sides.iter().map(|s| match self.state(&BodyLocation::Arm(*s)) {
+                PartState::Missing => -2,
+                PartState::Disabled | PartState::Prosthetic => -1,
+                PartState::Intact => 0,
+            }).sum(),
+            StatBase::App => -eyes_lost,
+            _ => 0
+        }
+    }
+}
+
+#[cfg(test)]
+mod anatomy_tests {
+    use crate::rng::SeededRng;
+
+    use super::*;
+
+    #[test]
+    fn losing_a_hand_takes_fingers_along() {
+        let mut a = Anatomy::new();
+        assert_eq!(FINGERS_PER_HAND, a.fingers(Bilateral::Right));
+        a.lose(BodyLocation::Arm(Bilateral::Right));
+        assert_eq!(PartState::Missing, a.state(&BodyLocation::Hand(Bilateral::Right)));
+        assert_eq!(PartState::Missing, a.state(&BodyLocation::Thumb(Bilateral::Right)));
+        assert_eq!(0, a.fingers(Bilateral::Right));
+        assert_eq!(-3, a.penalty(&StatBase::Dex));
+        assert_eq!(-2, a.penalty(&StatBase::Str));
+    }
+
+    #[test]
+    fn fingers_and_eyes() {
+        let mut a = Anatomy::new();
+        a.set(BodyLocation::Fingers { count: 3, side: Bilateral::Left }, PartState::Missing);
+        assert_eq!(1, a.fingers(Bilateral::Left));
+        assert!(a.is_present(&BodyLocation::Fingers { count: 1, side: Bilateral::Left }));
+        a.lose_fingers(Bilateral::Left, 5);
+        assert!(!a.is_present(&BodyLocation::Fingers { count: 1, side: Bilateral::Left }));
+        a.lose(BodyLocation::Eye(Bilateral::Left));
+        assert_eq!(1, a.eyes());
+        a.set(BodyLocation::Eye(Bilateral::Left), PartState::Prosthetic);
+        assert_eq!(2, a.eyes());
+        assert_eq!(0, a.penalty(&StatBase::App));
+        assert_eq!(-4, a.penalty(&StatBase::Dex));
+    }
+
+    #[test]
+    fn extra_fingers_dont_load() {
+        assert!(serde_json::from_str::<Anatomy>(r#"{"fingers": [5, 5]}"#).is_err());
+        let a: Anatomy = serde_json::from_str(r#"{"fingers": [4, 1]}"#).unwrap();
+        assert_eq!(-3, a.penalty(&StatBase::Dex));
+    }
+
+    #[test]
+    fn no_front_or_back_hands() {
+        let mut a = Anatomy::new();
+        let front = BodyLocation::Fingers { count: 1, side: Bilateral::Front };
+        assert_eq!(0, a.fingers(Bilateral::Back));
+        assert_eq!(PartState::Missing, a.state(&front));
+        assert!(!a.is_present(&front));
+        a.set(front, PartState::Missing);
+        a.lose_fingers(Bilateral::Back, 1);
+        assert_eq!(0, a.penalty(&StatBase::Dex));
+    }
+
+    #[test]
+    fn random_hits_skip_missing_parts() {
+        let mut a = Anatomy::new();
+        a.lose(BodyLocation::Leg(Bilateral::Left));
+        a.lose(BodyLocation::Arm(Bilateral::Right));
+        let mut rng = SeededRng::new(11);
+        for _ in 0..200 {
+            let loc = a.random_location_with(&mut rng);
+            assert!(a.is_present(&loc), "hit missing {loc}");
+        }
+    }
+
+    #[test]
+    fn setting_intact_restores() {
+        let mut a = Anatomy::new();
+        a.set(BodyLocation::Leg(Bilateral::Left), PartState::Disabled);
+        assert_eq!(-1, a.penalty(&StatBase::Dex));
+        a.set(BodyLocation::Leg(Bilateral::Left), PartState::Intact);
+        assert_eq!(0, a.damaged().count());
+        assert_eq!(0, a.penalty(&StatBase::Dex));
+    }
+}
@@ -0,0 +1,268 @@
+//! Armor coverage - which [ArmorPiece] covers which [BodyLocation], and how well.
+//!
+//! Pieces are worn in layers within an [ArmorSet]; a hit goes through the
+//! layers outermost first, each stopping as much damage as its protection
+//! at the hit location allows.
+//!
+//! ```
+//! use rpgassist::body::{armor::{ArmorPiece, ArmorSet}, location::BodyLocation};
+//!
+//! let mut armor = ArmorSet::new();
+//! armor.wear(ArmorPiece::new("gambeson").covering(BodyLocation::Chest, 2).covering_both(BodyLocation::Arm, 1));
+//! armor.wear(ArmorPiece::new("breastplate").covering(BodyLocation::Chest, 5));
+//! let hit = armor.hit(BodyLocation::Chest, 10);
+//! assert_eq!(7, hit.stopped);
+//! assert_eq!(3, hit.damage);
+//! ```
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{body::{called_shot::CalledShot, location::BodyLocation}, direction::bilateral::Bilateral, ext::IsNamed, rng::{ChaosRng, DiceRng}};
+
+/// Protection a piece gives at a single location.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    pub location: BodyLocation,
+    pub protection: i32,
+}
+
+/// A single piece of armor.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ArmorPiece {
+    name: String,
+    #[serde(default)]
+    coverage: Vec<Coverage>,
+}
+
+impl ArmorPiece {
+    /// A new piece which (so far) covers nothing.
+    pub fn new(name: &str) -> Self {
+        Self { name: name.into(), coverage: vec![] }
+    }
+
+    /// Cover the `location` with the given `protection`.
+    ///
+    /// Covering the same spot again replaces the earlier protection value.
+    pub fn covering(mut self, location: BodyLocation, protection: i32) -> Self {
+        self.coverage.retain(|c| !c.location.same_spot(&location));
+        self.coverage.push(Coverage { location, protection });
+        self
+    }
+
+    /// Cover both left and right `location`, e.g. `covering_both(BodyLocation::Arm, 2)`.
+    pub fn covering_both<F: Fn(Bilateral) -> BodyLocation>(self, location: F, protection: i32) -> Self {
+        self.covering(location(Bilateral::Left), protection)
+            .covering(location(Bilateral::Right), protection)
+    }
+
+    /// Get the [Coverage] list.
+    pub fn coverage(&self) -> &[Coverage] {
+        &self.coverage
+    }
+
+    /// See if the piece covers the `location`.
+    pub fn covers(&self, location: &BodyLocation) -> bool {
+        self.protection_at(location).is_some()
+    }
+
+    /// Get protection at the `location`, if covered at all.
+    ///
+    /// [Fingers][BodyLocation::Fingers] are covered regardless of their count.
+    pub fn protection_at(&self, location: &BodyLocation) -> Option<i32> {
+        self.coverage.iter()
+            .find(|c| c.location.same_spot(location))
+            .map(|c| c.protection)
+    }
+}
+
+impl IsNamed for ArmorPiece {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Display for ArmorPiece {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// A layer hit by an [ArmorHit].
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct LayerHit {
+    /// Name of the [ArmorPiece].
+    pub piece: String,
+    pub protection: i32,
+    /// How much damage the layer stopped.
+    pub stopped: i32,
+}
+
+/// Outcome of a hit against an [ArmorSet].
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ArmorHit {
+    pub location: BodyLocation,
+    /// The layers hit, outermost first.
+    pub layers: Vec<LayerHit>,
+    /// Total damage stopped by the armor.
+    pub stopped: i32,
+    /// Damage which got through.
+    pub damage: i32,
+}
+
+impl ArmorHit {
+    /// Did the hit land on an unarmored spot?
+    pub fn unarmored(&self) -> bool {
+        self.layers.is_empty()
+    }
+}
+
+impl Display for ArmorHit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.unarmored() {
+            return write!(f, "{} (unarmored): {} damage", self.location, self.damage)
+        }
+        let layers: Vec<String> = self.layers.iter().map(|l| l.piece.clone()).collect();
+        write!(f, "{} ({}): {} stopped, {} damage", self.location, layers.join(", "), self.stopped, self.damage)
+    }
+}
+
+/// Layered armor pieces worn by someone.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct ArmorSet {
+    /// Pieces worn, innermost first.
+    pieces: Vec<ArmorPiece>,
+}
+
+impl ArmorSet {
+    /// No armor (yet).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Put on a piece, on top of (i.e. outside) anything worn so far.
+    pub fn wear(&mut self, piece: ArmorPiece) {
+        self.pieces.push(piece)
+    }
+
+    /// Take off piece(s) with the given `name`. Returns what was taken off.
+    pub fn remove(&mut self, name: &str) -> Vec<ArmorPiece> {
+        let (off, on) = std::mem::take(&mut self.pieces).into_iter().partition(|p| p.name == name);
+        self.pieces = on;
+        off
+    }
+
+    /// Get the pieces worn, innermost first.
+    pub fn pieces(&self) -> &[ArmorPiece] {
+        &self.pieces
+    }
+
+    /// Get the pieces covering the `location`, outermost first.
+    pub fn layers_at<'a>(&'a self, location: &'a BodyLocation) -> impl Iterator<Item = &'a ArmorPiece> {
+        self.pieces.iter().rev().filter(move |p| p.covers(location))
+    }
+
+    /// Total protection at the `location`.
+    pub fn protection_at(&self, location: &BodyLocation) -> i32 {
+        self.layers_at(location).filter_map(|p| p.protection_at(location)).sum()
+    }
+
+    /// Resolve a hit of `damage` at the `location`.
+    pub fn hit(&self, location: BodyLocation, damage: i32) -> ArmorHit {
+        let mut left = damage.max(0);
+        let mut layers = vec![];
+        for piece in self.layers_at(&location) {
+            let protection = piece.protection_at(&location).unwrap_or(0);
+            let stopped = left.min(protection.max(0));
+            left -= stopped;
+            layers.push(LayerHit { piece: piece.name.clone(), protection, stopped });
+        }
+        ArmorHit { stopped: damage.max(0) - left, damage: left, location, layers }
+    }
+
+    /// Resolve a hit of `damage` at a random location.
+    pub fn hit_random(&self, damage: i32) -> ArmorHit {
+        self.hit_random_with(damage, &mut ChaosRng)
+    }
+
+    /// Resolve a hit of `damage` at a random location, using the given `rng`.
+    pub fn hit_random_with<R: DiceRng + ?Sized>(&self, damage: i32, rng: &mut R) -> ArmorHit {
+        self.hit(BodyLocation::random_with(rng), damage)
+    }
+
+    /// Resolve a [called shot][CalledShot] of `damage`.
+    pub fn hit_called(&self, shot: &CalledShot, damage: i32) -> ArmorHit {
+        self.hit_called_with(shot, damage, &mut ChaosRng)
+    }
+
+    /// Resolve a [called shot][CalledShot] of `damage`, using the given `rng`.
+    pub fn hit_called_with<R: DiceRng + ?Sized>(&self, shot: &CalledShot, damage: i32, rng: &mut R) -> ArmorHit {
+        self.hit(shot.roll_with(rng).location, damage)
+    }
+}
+
+#[cfg(test)]
+mod armor_tests {
+    use crate::{body::{called_shot::Aim, location::BodyRegion}, rng::SeededRng};
+
+    use super::*;
+
+    fn knight() -> ArmorSet {
+        let mut a = ArmorSet::new();
+        a.wear(ArmorPiece::new("gambeson")
+            .covering(BodyLocation::Chest, 2)
+            .covering(BodyLocation::Back, 2)
+            .covering(BodyLocation::Abdomen, 2)
+            .covering_both(BodyLocation::Arm, 1));
+        a.wear(ArmorPiece::new("breastplate").covering(BodyLocation::Chest, 5));
+        a.wear(ArmorPiece::new("gauntlet").covering(BodyLocation::Hand(Bilateral::Left), 3));
+        a
+    }
+
+    #[test]
+    fn layers_outermost_first() {
+        let a = knight();
+        let hit = a.hit(BodyLocation::Chest, 6);
+        assert_eq!(vec!["breastplate", "gambeson"], hit.layers.iter().map(|l| l.piece.as_str()).collect::<Vec<_>>());
+        assert_eq!(5, hit.layers[0].stopped);
+        assert_eq!(1, hit.layers[1].stopped);
+        assert_eq!(0, hit.damage);
+        assert_eq!(7, a.protection_at(&BodyLocation::Chest));
+    }
+
+    #[test]
+    fn sides_are_respected() {
+        let a = knight();
+        assert_eq!(3, a.protection_at(&BodyLocation::Hand(Bilateral::Left)));
+        assert_eq!(0, a.protection_at(&BodyLocation::Hand(Bilateral::Right)));
+        assert_eq!(1, a.protection_at(&BodyLocation::Arm(Bilateral::Right)));
+        assert!(a.hit(BodyLocation::Head, 4).unarmored());
+    }
+
+    #[test]
+    fn fingers_match_regardless_of_count() {
+        let glove = ArmorPiece::new("glove").covering(BodyLocation::Fingers { count: 5, side: Bilateral::Right }, 1);
+        assert!(glove.covers(&BodyLocation::Fingers { count: 2, side: Bilateral::Right }));
+        assert!(!glove.covers(&BodyLocation::Fingers { count: 2, side: Bilateral::Left }));
+    }
+
+    #[test]
+    fn called_shot_at_head_is_unarmored() {
+        let a = knight();
+        let shot = CalledShot::new(Aim::Region(BodyRegion::Head));
+        let mut rng = SeededRng::new(10);
+        for _ in 0..20 {
+            let hit = a.hit_called_with(&shot, 5, &mut rng);
+            assert_eq!(5, hit.damage);
+        }
+    }
+
+    #[test]
+    fn remove_and_json_round_trip() {
+        let mut a = knight();
+        assert_eq!(1, a.remove("breastplate").len());
+        assert_eq!(2, a.protection_at(&BodyLocation::Chest));
+        let json = serde_json::to_string(&a).unwrap();
+        assert_eq!(a, serde_json::from_str(&json).unwrap());
+    }
+}
@@ -0,0 +1,222 @@
+//! Called shots - aiming at a [region][BodyRegion], a side or a specific location.
+//!
+//! Aiming reshapes the [T867][BodyLocation::table] odds: only locations matching
+//! the [Aim] are left in, keeping their odds relative to each other. A shot can
+//! also go a bit astray and [scatter][CalledShot::with_scatter] onto an
+//! [adjacent][BodyLocation::adjacent] location instead.
+//!
+//! ```
+//! use rpgassist::{body::{called_shot::{Aim, CalledShot}, location::{BodyLocation, BodyRegion}}, rng::SeededRng};
+//!
+//! let shot = CalledShot::new(Aim::Region(BodyRegion::Head)).with_scatter(25);
+//! let hit = shot.roll_with(&mut SeededRng::new(867));
+//! if !hit.scattered() {
+//!     assert_eq!(BodyRegion::Head, hit.location.region());
+//! }
+//! ```
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{audit::RollRecord, body::location::{BodyLocation, BodyRegion}, direction::bilateral::Bilateral, rng::{ChaosRng, DiceRng}, table::RollTable};
+
+/// What's being aimed at.
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, PartialEq, Eq)]
+pub enum Aim {
+    Region(BodyRegion),
+    /// A [side][BodyLocation::side] of the body, e.g. left.
+    Side(Bilateral),
+    Location(BodyLocation),
+}
+
+impl Aim {
+    /// See if the `location` is within the aim.
+    pub fn covers(&self, location: &BodyLocation) -> bool {
+        match self {
+            Self::Region(r) => location.region() == *r,
+            Self::Side(s) => location.side() == Some(*s),
+            Self::Location(l) => location == l,
+        }
+    }
+
+    /// Get the hit location table reshaped for the aim.
+    ///
+    /// # Returns
+    /// `None` if nothing on [T867][BodyLocation::table] matches the aim,
+    /// e.g. when aiming at a [thumb][BodyLocation::Thumb].
+    pub fn table(&self) -> Option<RollTable<BodyLocation>> {
+        BodyLocation::table().retain(|l| self.covers(l))
+    }
+}
+
+impl Display for Aim {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Region(r) => write!(f, "{r}"),
+            Self::Side(s) => write!(f, "{s} side"),
+            Self::Location(l) => write!(f, "{l}"),
+        }
+    }
+}
+
+/// A called shot.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct CalledShot {
+    aim: Aim,
+    /// Chance (%) of the hit scattering onto an adjacent location.
+    scatter: u8,
+}
+
+impl CalledShot {
+    /// A called shot at the given `aim`, with no chance of scattering.
+    pub fn new(aim: Aim) -> Self {
+        Self { aim, scatter: 0 }
+    }
+
+    /// Set chance (%) of the hit scattering onto an adjacent location.
+    pub fn with_scatter(mut self, percent: u8) -> Self {
+        self.scatter = percent.min(100);
+        self
+    }
+
+    /// Get the [Aim].
+    pub fn aim(&self) -> &Aim {
+        &self.aim
+    }
+
+    /// Get the scatter chance (%).
+    pub fn scatter(&self) -> u8 {
+        self.scatter
+    }
+
+    /// Resolve where the called shot hits.
+    pub fn roll(&self) -> CalledHit {
+        self.roll_with(&mut ChaosRng)
+    }
+
+    /// Resolve where the called shot hits, using the given `rng`.
+    ///
+    /// An aim which matches nothing on [T867][BodyLocation::table], e.g. a
+    /// [thumb][BodyLocation::Thumb], hits its [location][Aim::Location] directly
+    /// (before scatter).
+    pub fn roll_with<R: DiceRng + ?Sized>(&self, rng: &mut R) -> CalledHit {
+        let intended = match (self.aim.table(), &self.aim) {
+            (Some(t), _) => t.roll_recorded_with(rng),
+            (None, Aim::Location(l)) => l.clone(),
+            (None, _) => BodyLocation::table().roll_recorded_with(rng),
+        };
+        let location = if self.scatter > 0 {
+            let roll = rng.roll_die(100);
+            let scattered = roll <= self.scatter as u32;
+            if rng.is_recording() {
+                rng.record(RollRecord::new("CalledShot::scatter", "1d100", vec![roll], if scattered { "scatter" } else { "on target" }));
+            }
+            if scattered {
+                intended.scatter_with(rng)
+            } else {
+                intended.clone()
+            }
+        } else {
+            intended.clone()
+        };
+        CalledHit { aim: self.aim.clone(), intended, location }
+    }
+}
+
+impl BodyLocation {
+    /// Pick one of the [adjacent][BodyLocation::adjacent] locations at random.
+    pub fn scatter(&self) -> Self {
+        self.scatter_with(&mut ChaosRng)
+    }
+
+    /// Pick one of the [adjacent][BodyLocation::adjacent] locations at random, using the given `rng`.
+    pub fn scatter_with<R: DiceRng + ?Sized>(&self, rng: &mut R) -> Self {
+        let adjacent = self.adjacent();
+        let roll = rng.roll_die(adjacent.len() as u32);
+        let location = adjacent[roll as usize - 1].clone();
+        if rng.is_recording() {
+            rng.record(RollRecord::new("BodyLocation::scatter", format!("1d{}", adjacent.len()), vec![roll], &location));
+        }
+        location
+    }
+}
+
+/// Outcome of a [CalledShot].
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct CalledHit {
+    pub aim: Aim,
+    /// Where the shot was going to hit.
+    pub intended: BodyLocation,
+    /// Where the shot actually hit.
+    pub location: BodyLocation,
+}
+
+impl CalledHit {
+    /// Did the hit scatter off the intended location?
+    pub fn scattered(&self) -> bool {
+        self.intended != self.location
+    }
+}
+
+impl Display for CalledHit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.scattered() {
+            write!(f, "{} (aimed at {}, went astray from {})", self.location, self.aim, self.intended)
+        } else {
+            write!(f, "{} (aimed at {})", self.location, self.aim)
+        }
+    }
+}
+
+#[cfg(test)]
+mod called_shot_tests {
+    use crate::{audit::AuditedRng, rng::SeededRng};
+
+    use super::*;
+
+    #[test]
+    fn head_aim_reshapes_odds() {
+        let odds = Aim::Region(BodyRegion::Head).table().unwrap().odds();
+        // T867: head 1/20, face 2/20 …
+        assert!((odds.probability(&BodyLocation::Head) - 1.0 / 3.0).abs() < 1e-9);
+        assert!((odds.probability(&BodyLocation::Face) - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn lower_body_keeps_sub_table_odds() {
+        let odds = Aim::Region(BodyRegion::LowerBody).table().unwrap().odds();
+        // 1/20 + 2/60 buttocks vs. 1/60 genitals, out of 8/20 total…
+        assert!((odds.probability(&BodyLocation::Genitals) - (1.0 / 60.0) / (8.0 / 20.0)).abs() < 1e-9);
+        assert!((odds.probability(&BodyLocation::Buttocks) - (5.0 / 60.0) / (8.0 / 20.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn left_side_only_hits_left() {
+        let shot = CalledShot::new(Aim::Side(Bilateral::Left));
+        let mut rng = SeededRng::new(1);
+        for _ in 0..100 {
+            let hit = shot.roll_with(&mut rng);
+            assert_eq!(Some(Bilateral::Left), hit.location.side());
+            assert!(!hit.scattered());
+        }
+    }
+
+    #[test]
+    fn scatter_goes_to_adjacent() {
+        let shot = CalledShot::new(Aim::Location(BodyLocation::Head)).with_scatter(100);
+        let mut rng = AuditedRng::new(SeededRng::new(2));
+        for _ in 0..20 {
+            let hit = shot.roll_with(&mut rng);
+            assert_eq!(BodyLocation::Head, hit.intended);
+            assert!(hit.scattered());
+            assert!(BodyLocation::Head.adjacent().contains(&hit.location));
+        }
+        assert!(rng.records().iter().any(|r| r.source == "CalledShot::scatter"));
+    }
+
+    #[test]
+    fn aim_outside_t867_hits_directly() {
+        let shot = CalledShot::new(Aim::Location(BodyLocation::Thumb(Bilateral::Right)));
+        assert_eq!(BodyLocation::Thumb(Bilateral::Right), shot.roll_with(&mut SeededRng::new(3)).location);
+    }
+}
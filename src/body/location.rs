@@ -1,13 +1,34 @@
 //! 867: Body Locations
-use std::fmt::Display;
+//!
+//! Locations also know their rough [region][BodyRegion], [side][BodyLocation::side]
+//! and which other locations they're [adjacent][BodyLocation::adjacent] to - used by
+//! e.g. [called shots][crate::body::called_shot].
+use std::{fmt::Display, sync::OnceLock};
 
-use dicebag::DiceExt;
 use serde::{Deserialize, Serialize};
 
-use crate::direction::bilateral::Bilateral;
+use crate::{direction::bilateral::Bilateral, rng::{ChaosRng, DiceRng}, table::RollTable};
+
+/// Coarse body regions, e.g. for aiming at.
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, Copy, PartialEq, Eq)]
+pub enum BodyRegion {
+    Head,
+    UpperBody,
+    LowerBody,
+}
+
+impl Display for BodyRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Head => "head",
+            Self::UpperBody => "upper body",
+            Self::LowerBody => "lower body",
+        })
+    }
+}
 
 /// A variety of body locations.
-#[derive(Debug, Deserialize, Serialize, Hash, Clone)]
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, PartialEq, Eq)]
 pub enum BodyLocation {
     Abdomen,
     Arm(Bilateral),
@@ -30,25 +51,98 @@ impl BodyLocation {
     /// 
     // Note that not all available [BodyLocation] are included in random generation.
     pub fn random() -> Self {
-        // T867
-        match 1.d20() {
-            ..=1 => Self::Foot(Bilateral::Right),
-            2 => Self::Foot(Bilateral::Left),
-            3 => Self::Leg(Bilateral::Right),
-            4 => Self::Leg(Bilateral::Left),
-            5|6 => Self::Abdomen,
-            7 => Self::Buttocks,
-            8 => if 1.d3() == 1 { Self::Genitals } else { Self::Buttocks },
-            9 => Self::Back,
-            10..=13 => Self::Chest,
-            14 => Self::Arm(Bilateral::Right),
-            15 => Self::Arm(Bilateral::Left),
-            16 => Self::Hand(Bilateral::Right),
-            17 => Self::Hand(Bilateral::Left),
-            18 => Self::Head,
-            _ => Self::Face
+        Self::random_with(&mut ChaosRng)
+    }
+
+    /// Generate a random body location using the given `rng`.
+    pub fn random_with<R: DiceRng + ?Sized>(rng: &mut R) -> Self {
+        Self::table().roll_recorded_with(rng)
+    }
+
+    /// Get the [region][BodyRegion] the location belongs to.
+    pub fn region(&self) -> BodyRegion {
+        match self {
+            Self::Eye(_) |
+            Self::Face |
+            Self::Head => BodyRegion::Head,
+            Self::Arm(_) |
+            Self::Back |
+            Self::Chest |
+            Self::Fingers { .. } |
+            Self::Hand(_) |
+            Self::Thumb(_) => BodyRegion::UpperBody,
+            Self::Abdomen |
+            Self::Buttocks |
+            Self::Foot(_) |
+            Self::Genitals |
+            Self::Leg(_) => BodyRegion::LowerBody,
+        }
+    }
+
+    /// Get the side of the body the location is on, if it has one.
+    ///
+    /// Left/right for paired locations, front/back for the torso, `None` for
+    /// the likes of [head][BodyLocation::Head].
+    pub fn side(&self) -> Option<Bilateral> {
+        match self {
+            Self::Arm(lr) |
+            Self::Eye(lr) |
+            Self::Foot(lr) |
+            Self::Hand(lr) |
+            Self::Leg(lr) |
+            Self::Thumb(lr) |
+            Self::Fingers { side: lr, .. } => Some(*lr),
+            Self::Abdomen |
+            Self::Chest |
+            Self::Face |
+            Self::Genitals => Some(Bilateral::Front),
+            Self::Back |
+            Self::Buttocks => Some(Bilateral::Back),
+            Self::Head => None,
+        }
+    }
+
+    /// See if `other` is the same spot on the body as this one.
+    ///
+    /// Mostly plain equality, except [fingers][BodyLocation::Fingers] match
+    /// regardless of their count.
+    pub fn same_spot(&self, other: &BodyLocation) -> bool {
+        match (self, other) {
+            (Self::Fingers { side: a, .. }, Self::Fingers { side: b, .. }) => a == b,
+            _ => self == other
         }
     }
+
+    /// Get the locations directly adjacent to this one, e.g. where a slightly
+    /// off hit lands instead.
+    pub fn adjacent(&self) -> Vec<BodyLocation> {
+        use Bilateral::{Left, Right};
+        match self {
+            Self::Abdomen => vec![Self::Chest, Self::Back, Self::Buttocks, Self::Genitals, Self::Leg(Left), Self::Leg(Right)],
+            Self::Arm(lr) => vec![Self::Chest, Self::Back, Self::Hand(*lr)],
+            Self::Back => vec![Self::Head, Self::Chest, Self::Abdomen, Self::Buttocks, Self::Arm(Left), Self::Arm(Right)],
+            Self::Buttocks => vec![Self::Back, Self::Abdomen, Self::Leg(Left), Self::Leg(Right)],
+            Self::Chest => vec![Self::Head, Self::Back, Self::Abdomen, Self::Arm(Left), Self::Arm(Right)],
+            Self::Eye(_) => vec![Self::Face],
+            Self::Face => vec![Self::Head, Self::Eye(Left), Self::Eye(Right)],
+            Self::Fingers { side, .. } => vec![Self::Hand(*side), Self::Thumb(*side)],
+            Self::Foot(lr) => vec![Self::Leg(*lr)],
+            Self::Genitals => vec![Self::Abdomen, Self::Leg(Left), Self::Leg(Right)],
+            Self::Hand(lr) => vec![Self::Arm(*lr), Self::Fingers { count: 1, side: *lr }, Self::Thumb(*lr)],
+            Self::Head => vec![Self::Face, Self::Chest, Self::Back],
+            Self::Leg(lr) => vec![Self::Abdomen, Self::Buttocks, Self::Genitals, Self::Foot(*lr)],
+            Self::Thumb(lr) => vec![Self::Hand(*lr), Self::Fingers { count: 1, side: *lr }],
+        }
+    }
+
+    /// Get the T867 [RollTable].
+    ///
+    /// The table data itself lives in the bundled `data/tables/t867.json`.
+    pub fn table() -> &'static RollTable<BodyLocation> {
+        static T867: OnceLock<RollTable<BodyLocation>> = OnceLock::new();
+        T867.get_or_init(|| RollTable::from_json(include_str!("../../data/tables/t867.json"))
+            .expect("Bundled T867 data is broken?!"))
+    }
 }
 
 impl Display for BodyLocation {
@@ -70,4 +164,29 @@ impl Display for BodyLocation {
             Self::Thumb(lr) => write!(f, "{lr} thumb"),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod body_location_tests {
+    use crate::id::HasId;
+
+    use super::*;
+
+    #[test]
+    fn bundled_t867_loads() {
+        let t = BodyLocation::table();
+        assert_eq!(867, t.id());
+        assert!(t.validate().is_ok());
+    }
+
+    #[test]
+    fn adjacency_is_mutual() {
+        let all = BodyLocation::table().odds().outcomes().map(|(l, _)| l.clone()).collect::<Vec<_>>();
+        for loc in &all {
+            for adj in loc.adjacent() {
+                let back = adj.adjacent().iter().any(|l| l.same_spot(loc));
+                assert!(back, "{adj} isn't adjacent to {loc}");
+            }
+        }
+    }
+}
@@ -0,0 +1,397 @@
+//! Data-driven body plans - for creatures that don't fit [BodyLocation][crate::body::location::BodyLocation].
+//!
+//! A [BodyPlan] is a named set of [BodyPart]s. Each part may come in
+//! [sides][Bilateral], have a count (five fingers, …), a parent part (hand
+//! → fingers) and a hit weight. A plan makes its own random hit location
+//! [table][BodyPlan::hit_table] out of the weights.
+//!
+//! Bundled plans: [humanoid][BodyPlan::humanoid], [quadruped][BodyPlan::quadruped],
+//! [serpent][BodyPlan::serpent], [winged][BodyPlan::winged] and
+//! [multi-armed][BodyPlan::multi_armed].
+//!
+//! ```json
+//! {
+//!     "name": "imp",
+//!     "parts": [
+//!         { "name": "body", "hit_weight": 6 },
+//!         { "name": "wing", "parent": "body", "sides": ["Left", "Right"], "hit_weight": 2 },
+//!         { "name": "claw", "parent": "wing", "sides": ["Left", "Right"], "count": 3 }
+//!     ]
+//! }
+//! ```
+use std::{fmt::Display, path::Path, sync::OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{dice::Dice, direction::bilateral::Bilateral, rng::{ChaosRng, DiceRng}, table::RollTable};
+
+fn default_count() -> u8 { 1 }
+
+/// A single (possibly sided) part of a [BodyPlan].
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct BodyPart {
+    pub name: String,
+    /// Sides the part comes in; empty for a single, central, part.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sides: Vec<Bilateral>,
+    /// How many of the part there are (per side).
+    #[serde(default = "default_count")]
+    pub count: u8,
+    /// Name of the part this one is attached to, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// Relative chance of getting hit (per side). Zero for "never directly".
+    #[serde(default)]
+    pub hit_weight: u32,
+}
+
+/// A specific location on a [BodyPlan] - a part, and its side if it has sides.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+pub struct PlanLocation {
+    pub part: String,
+    pub side: Option<Bilateral>,
+}
+
+impl Display for PlanLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.side {
+            Some(side) => write!(f, "{side} {}", self.part),
+            None => write!(f, "{}", self.part),
+        }
+    }
+}
+
+/// Something wrong with a [BodyPlan].
+#[derive(Debug)]
+pub enum BodyPlanError {
+    /// Reading the plan data failed.
+    Io(std::io::Error),
+    /// JSON didn't parse as a plan.
+    Json(serde_json::Error),
+    /// Two or more parts with the same name.
+    DuplicatePart(String),
+    /// Part attached to a part that doesn't exist.
+    UnknownParent { part: String, parent: String },
+    /// Parts attached to each other in a loop.
+    ParentCycle(String),
+    /// No part has any hit weight.
+    NoHitLocations,
+    /// Hit weights add up to more than a table can hold.
+    WeightOverflow,
+    /// Part with a count of zero.
+    ZeroCount(String),
+    /// Part with the same side listed more than once.
+    DuplicateSide { part: String, side: Bilateral },
+}
+
+impl Display for BodyPlanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Cannot read body plan data: {e}"),
+            Self::Json(e) => write!(f, "Cannot parse body plan data: {e}"),
+            Self::DuplicatePart(p) => write!(f, "Part '{p}' defined more than once"),
+            Self::UnknownParent { part, parent } => write!(f, "Part '{part}' attached to unknown part '{parent}'"),
+            Self::ParentCycle(p) => write!(f, "Part '{p}' is (eventually) attached to itself"),
+            Self::NoHitLocations => write!(f, "No part can be hit"),
+            Self::WeightOverflow => write!(f, "Hit weights add up to way too much"),
+            Self::ZeroCount(p) => write!(f, "Part '{p}' has a count of zero"),
+            Self::DuplicateSide { part, side } => write!(f, "Part '{part}' has side {side} more than once"),
+        }
+    }
+}
+
+impl std::error::Error for BodyPlanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Json(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for BodyPlanError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for BodyPlanError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+/// A named set of [BodyPart]s.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BodyPlan {
+    name: String,
+    /// Id for the plan's [hit table][BodyPlan::hit_table].
+    #[serde(default)]
+    id: usize,
+    parts: Vec<BodyPart>,
+    /// Hit table, built on first use.
+    #[serde(skip)]
+    table: OnceLock<RollTable<PlanLocation>>,
+}
+
+impl PartialEq for BodyPlan {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.id == other.id && self.parts == other.parts
+    }
+}
+
+impl Eq for BodyPlan {}
+
+impl BodyPlan {
+    /// Load a plan from JSON and [validate][BodyPlan::validate] it.
+    pub fn from_json(json: &str) -> Result<Self, BodyPlanError> {
+        let plan: Self = serde_json::from_str(json)?;
+        plan.validate()?;
+        Ok(plan)
+    }
+
+    /// Load a plan from a JSON file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, BodyPlanError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Bundled humanoid plan; hit weights match T867.
+    pub fn humanoid() -> &'static BodyPlan {
+        static PLAN: OnceLock<BodyPlan> = OnceLock::new();
+        PLAN.get_or_init(|| Self::from_json(include_str!("../../data/bodyplans/humanoid.json")).expect("Bundled humanoid plan is broken?!"))
+    }
+
+    /// Bundled four-legged plan.
+    pub fn quadruped() -> &'static BodyPlan {
+        static PLAN: OnceLock<BodyPlan> = OnceLock::new();
+        PLAN.get_or_init(|| Self::from_json(include_str!("../../data/bodyplans/quadruped.json")).expect("Bundled quadruped plan is broken?!"))
+    }
+
+    /// Bundled serpentine plan.
+    pub fn serpent() -> &'static BodyPlan {
+        static PLAN: OnceLock<BodyPlan> = OnceLock::new();
+        PLAN.get_or_init(|| Self::from_json(include_str!("../../data/bodyplans/serpent.json")).expect("Bundled serpent plan is broken?!"))
+    }
+
+    /// Bundled winged (bird-like) plan.
+    pub fn winged() -> &'static BodyPlan {
+        static PLAN: OnceLock<BodyPlan> = OnceLock::new();
+        PLAN.get_or_init(|| Self::from_json(include_str!("../../data/bodyplans/winged.json")).expect("Bundled winged plan is broken?!"))
+    }
+
+    /// Bundled four-armed humanoid plan.
+    pub fn multi_armed() -> &'static BodyPlan {
+        static PLAN: OnceLock<BodyPlan> = OnceLock::new();
+        PLAN.get_or_init(|| Self::from_json(include_str!("../../data/bodyplans/multi_armed.json")).expect("Bundled multi-armed plan is broken?!"))
+    }
+
+    /// Validate the plan: unique part names, non-zero counts, unique sides,
+    /// existing parents, no parent loops, and at least one hittable part.
+    pub fn validate(&self) -> Result<(), BodyPlanError> {
+        for (i, p) in self.parts.iter().enumerate() {
+            if self.parts[..i].iter().any(|q| q.name == p.name) {
+                return Err(BodyPlanError::DuplicatePart(p.name.clone()))
+            }
+            if p.count == 0 {
+                return Err(BodyPlanError::ZeroCount(p.name.clone()))
+            }
+            if let Some((_, side)) = p.sides.iter().enumerate().find(|(j, s)| p.sides[..*j].contains(s)) {
+                return Err(BodyPlanError::DuplicateSide { part: p.name.clone(), side: *side })
+            }
+        }
+        for p in &self.parts {
+            let mut curr = p;
+            for _ in 0..=self.parts.len() {
+                let Some(parent) = &curr.parent else { break };
+                let Some(next) = self.part(parent) else {
+                    return Err(BodyPlanError::UnknownParent { part: curr.name.clone(), parent: parent.clone() })
+                };
+                if next.name == p.name {
+                    return Err(BodyPlanError::ParentCycle(p.name.clone()))
+                }
+                curr = next;
+            }
+        }
+        if self.parts.iter().all(|p| p.hit_weight == 0) {
+            return Err(BodyPlanError::NoHitLocations)
+        }
+        self.total_weight().ok_or(BodyPlanError::WeightOverflow)?;
+        Ok(())
+    }
+
+    /// Get the total hit weight of all the locations, if it fits a table.
+    fn total_weight(&self) -> Option<u32> {
+        self.parts.iter()
+            .try_fold(0u32, |t, p| p.hit_weight.checked_mul(p.sides.len().max(1) as u32)?.checked_add(t))
+            .filter(|t| *t <= i32::MAX as u32)
+    }
+
+    /// Get the plan's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get all the parts.
+    pub fn parts(&self) -> &[BodyPart] {
+        &self.parts
+    }
+
+    /// Get a part by its `name`.
+    pub fn part(&self, name: &str) -> Option<&BodyPart> {
+        self.parts.iter().find(|p| p.name == name)
+    }
+
+    /// Get the parts directly attached to the part named `name`.
+    pub fn children(&self, name: &str) -> Vec<&BodyPart> {
+        self.parts.iter().filter(|p| p.parent.as_deref() == Some(name)).collect()
+    }
+
+    /// Get all the [locations][PlanLocation], i.e. parts times their sides.
+    pub fn locations(&self) -> Vec<PlanLocation> {
+        self.parts.iter().flat_map(Self::locations_of).collect()
+    }
+
+    fn locations_of(part: &BodyPart) -> Vec<PlanLocation> {
+        if part.sides.is_empty() {
+            vec![PlanLocation { part: part.name.clone(), side: None }]
+        } else {
+            part.sides.iter().map(|s| PlanLocation { part: part.name.clone(), side: Some(*s) }).collect()
+        }
+    }
+
+    /// Get the location the given `loc` is attached to, if any.
+    ///
+    /// A sided child of a sided parent is attached to the parent's same side.
+    pub fn parent_of(&self, loc: &PlanLocation) -> Option<PlanLocation> {
+        let parent = self.part(self.part(&loc.part)?.parent.as_ref()?)?;
+        let side = if parent.sides.is_empty() { None } else { loc.side };
+        Some(PlanLocation { part: parent.name.clone(), side })
+    }
+
+    /// Get the locations directly attached to the given `loc`.
+    pub fn children_of(&self, loc: &PlanLocation) -> Vec<PlanLocation> {
+        self.children(&loc.part).into_iter()
+            .flat_map(Self::locations_of)
+            .filter(|c| loc.side.is_none() || c.side.is_none() || c.side == loc.side)
+            .collect()
+    }
+
+    /// Get a random hit location table built out of the part hit weights.
+    ///
+    /// # Panic
+    /// If the hit weights add up to more than a table can hold, which
+    /// [validation][BodyPlan::validate] would have caught.
+    pub fn hit_table(&self) -> &RollTable<PlanLocation> {
+        self.table.get_or_init(|| self.build_hit_table())
+    }
+
+    fn build_hit_table(&self) -> RollTable<PlanLocation> {
+        let total = self.total_weight().expect("Body plan hit weights overflow - not validated?");
+        let mut table = RollTable::new(self.id, Dice::new(1, total)).named(&self.name);
+        let mut next = 1;
+        for p in self.parts.iter().filter(|p| p.hit_weight > 0) {
+            for loc in Self::locations_of(p) {
+                let last = next + p.hit_weight as i32 - 1;
+                table = table.with_value(next..=last, loc);
+                next = last + 1;
+            }
+        }
+        table
+    }
+
+    /// Generate a random hit location.
+    pub fn random_location(&self) -> PlanLocation {
+        self.random_location_with(&mut ChaosRng)
+    }
+
+    /// Generate a random hit location using the given `rng`.
+    pub fn random_location_with<R: DiceRng + ?Sized>(&self, rng: &mut R) -> PlanLocation {
+        self.hit_table().roll_recorded_with(rng)
+    }
+}
+
+#[cfg(test)]
+mod body_plan_tests {
+    use crate::{body::location::BodyLocation, id::HasId, rng::SeededRng};
+
+    use super::*;
+
+    #[test]
+    fn bundled_plans_are_cached() {
+        assert!(std::ptr::eq(BodyPlan::humanoid(), BodyPlan::humanoid()));
+        let plan = BodyPlan::winged();
+        assert!(std::ptr::eq(plan.hit_table(), plan.hit_table()));
+        assert_eq!(plan, &plan.clone());
+    }
+
+    #[test]
+    fn bundled_plans_load() {
+        let plans = [BodyPlan::humanoid(), BodyPlan::quadruped(), BodyPlan::serpent(), BodyPlan::winged(), BodyPlan::multi_armed()];
+        for plan in plans {
+            assert!(plan.hit_table().validate().is_ok(), "{} hit table is broken", plan.name());
+        }
+        // each shows up in audit records as a table of its own
+        let mut ids: Vec<usize> = plans.iter().map(|p| p.hit_table().id()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(plans.len(), ids.len());
+        assert!(!ids.contains(&0));
+    }
+
+    #[test]
+    fn humanoid_matches_t867() {
+        let plan = BodyPlan::humanoid().hit_table().odds();
+        let t867 = BodyLocation::table().odds();
+        for (loc, p) in t867.outcomes() {
+            let (part, side) = match loc {
+                BodyLocation::Arm(s) => ("arm".to_string(), Some(*s)),
+                BodyLocation::Foot(s) => ("foot".to_string(), Some(*s)),
+                BodyLocation::Hand(s) => ("hand".to_string(), Some(*s)),
+                BodyLocation::Leg(s) => ("leg".to_string(), Some(*s)),
+                other => (other.to_string(), None),
+            };
+            let q = plan.probability(&PlanLocation { part, side });
+            assert!((p - q).abs() < 1e-9, "{loc}: {p} vs {q}");
+        }
+    }
+
+    #[test]
+    fn parents_and_children() {
+        let plan = BodyPlan::humanoid();
+        let hand = PlanLocation { part: "hand".into(), side: Some(Bilateral::Left) };
+        assert_eq!(Some(PlanLocation { part: "arm".into(), side: Some(Bilateral::Left) }), plan.parent_of(&hand));
+        let kids = plan.children_of(&hand);
+        assert_eq!(2, kids.len());
+        assert!(kids.iter().all(|k| k.side == Some(Bilateral::Left)));
+        assert_eq!(4, plan.part("finger").unwrap().count);
+    }
+
+    #[test]
+    fn serpent_has_no_limbs() {
+        let plan = BodyPlan::serpent();
+        let mut rng = SeededRng::new(7);
+        for _ in 0..100 {
+            let loc = plan.random_location_with(&mut rng);
+            assert!(["head", "forebody", "midsection", "hindbody", "tail"].contains(&loc.part.as_str()));
+        }
+    }
+
+    #[test]
+    fn broken_plans_are_errors() {
+        let dup = r#"{ "name": "x", "parts": [{ "name": "a", "hit_weight": 1 }, { "name": "a" }] }"#;
+        assert!(matches!(BodyPlan::from_json(dup), Err(BodyPlanError::DuplicatePart(_))));
+        let orphan = r#"{ "name": "x", "parts": [{ "name": "a", "parent": "b", "hit_weight": 1 }] }"#;
+        assert!(matches!(BodyPlan::from_json(orphan), Err(BodyPlanError::UnknownParent { .. })));
+        let cycle = r#"{ "name": "x", "parts": [{ "name": "a", "parent": "b", "hit_weight": 1 }, { "name": "b", "parent": "a" }] }"#;
+        assert!(matches!(BodyPlan::from_json(cycle), Err(BodyPlanError::ParentCycle(_))));
+        let ghost = r#"{ "name": "x", "parts": [{ "name": "a" }] }"#;
+        assert!(matches!(BodyPlan::from_json(ghost), Err(BodyPlanError::NoHitLocations)));
+        let heavy = r#"{ "name": "x", "parts": [{ "name": "a", "sides": ["Left", "Right"], "hit_weight": 4000000000 }] }"#;
+        assert!(matches!(BodyPlan::from_json(heavy), Err(BodyPlanError::WeightOverflow)));
+        let none = r#"{ "name": "x", "parts": [{ "name": "a", "count": 0, "hit_weight": 1 }] }"#;
+        assert!(matches!(BodyPlan::from_json(none), Err(BodyPlanError::ZeroCount(_))));
+        let twice = r#"{ "name": "x", "parts": [{ "name": "a", "sides": ["Left", "Left"], "hit_weight": 1 }] }"#;
+        assert!(matches!(BodyPlan::from_json(twice), Err(BodyPlanError::DuplicateSide { side: Bilateral::Left, .. })));
+    }
+}
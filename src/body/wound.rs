@@ -0,0 +1,306 @@
+//! Wounds and injuries at [body locations][BodyLocation].
+//!
+//! # State transitions
+//!
+//! A [Wound] starts out [fresh][WoundState::Fresh]. It may get
+//! [treated][WoundState::Treated], and either way starts [healing][WoundState::Healing]
+//! as days pass - except [serious][Severity::Serious] and worse wounds, which
+//! don't heal at all untreated. Once healed, it's either [gone][WoundState::Healed]
+//! or leaves a [scar][WoundState::Scarred] (serious and worse).
+//!
+//! # `Wounds`
+//!
+//! A per-character wound list, which can be summarized for hummie eyes, e.g.
+//! `"deep cut on the left arm and bruised chest"`.
+//!
+//! Wounds work with any displayable location type, e.g. [PlanLocation][crate::body::plan::PlanLocation],
+//! but default to [BodyLocation].
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{body::location::BodyLocation, ext::NaturalJoin};
+
+/// What sort of trauma caused the wound.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WoundKind {
+    Cut,
+    Pierce,
+    Crush,
+    Burn,
+}
+
+/// How bad the wound is.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Minor,
+    Moderate,
+    Serious,
+    Critical,
+}
+
+impl Severity {
+    /// Default healing time, in days.
+    pub fn healing_days(&self) -> u32 {
+        match self {
+            Self::Minor => 3,
+            Self::Moderate => 10,
+            Self::Serious => 30,
+            Self::Critical => 90,
+        }
+    }
+}
+
+/// Where the wound is in its healing process.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WoundState {
+    Fresh,
+    Treated,
+    Healing,
+    /// Healed without a trace.
+    Healed,
+    /// Healed, but left a scar.
+    Scarred,
+}
+
+impl WoundState {
+    /// Is the wound done healing?
+    pub fn is_healed(&self) -> bool {
+        matches!(self, Self::Healed | Self::Scarred)
+    }
+}
+
+/// An illegal [WoundState] transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WoundStateError {
+    pub from: WoundState,
+    pub to: WoundState,
+}
+
+impl Display for WoundStateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "A wound cannot go from {:?} to {:?}", self.from, self.to)
+    }
+}
+
+impl std::error::Error for WoundStateError {}
+
+/// A single wound.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Wound<L = BodyLocation> {
+    kind: WoundKind,
+    severity: Severity,
+    location: L,
+    /// Days of healing still left.
+    healing_days: u32,
+    state: WoundState,
+}
+
+impl<L> Wound<L> {
+    /// Make a new, [fresh][WoundState::Fresh], wound with default healing time.
+    pub fn new(kind: WoundKind, severity: Severity, location: L) -> Self {
+        Self::with_healing_days(kind, severity, location, severity.healing_days())
+    }
+
+    /// Make a new, [fresh][WoundState::Fresh], wound with custom healing time.
+    pub fn with_healing_days(kind: WoundKind, severity: Severity, location: L, days: u32) -> Self {
+        Self { kind, severity, location, healing_days: days, state: WoundState::Fresh }
+    }
+
+    /// Get the [kind][WoundKind] of the wound.
+    pub fn kind(&self) -> WoundKind {
+        self.kind
+    }
+
+    /// Get the [severity][Severity] of the wound.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Get the location of the wound.
+    pub fn location(&self) -> &L {
+        &self.location
+    }
+
+    /// Get the current [state][WoundState] of the wound.
+    pub fn state(&self) -> WoundState {
+        self.state
+    }
+
+    /// Days of healing still left.
+    pub fn healing_days(&self) -> u32 {
+        self.healing_days
+    }
+
+    /// Treat a [fresh][WoundState::Fresh] wound.
+    pub fn treat(&mut self) -> Result<(), WoundStateError> {
+        match self.state {
+            WoundState::Fresh => {
+                self.state = WoundState::Treated;
+                Ok(())
+            },
+            from => Err(WoundStateError { from, to: WoundState::Treated })
+        }
+    }
+
+    /// Let `days` pass. Returns the resulting state.
+    ///
+    /// Untreated [serious][Severity::Serious] (or worse) wounds stay [fresh][WoundState::Fresh].
+    pub fn heal(&mut self, days: u32) -> WoundState {
+        if days == 0 || self.state.is_healed() {
+            return self.state
+        }
+        if self.state == WoundState::Fresh && self.severity >= Severity::Serious {
+            return self.state
+        }
+        self.healing_days = self.healing_days.saturating_sub(days);
+        self.state = match self.healing_days {
+            0 if self.severity >= Severity::Serious => WoundState::Scarred,
+            0 => WoundState::Healed,
+            _ => WoundState::Healing,
+        };
+        self.state
+    }
+}
+
+impl<L: Display> Display for Wound<L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let loc = &self.location;
+        match self.state {
+            WoundState::Healed => return write!(f, "healed {loc}"),
+            WoundState::Scarred => return write!(f, "scar on the {loc}"),
+            _ => ()
+        }
+        match (self.kind, self.severity) {
+            (WoundKind::Cut, Severity::Minor) => write!(f, "scratch on the {loc}"),
+            (WoundKind::Cut, Severity::Moderate) => write!(f, "cut on the {loc}"),
+            (WoundKind::Cut, Severity::Serious) => write!(f, "deep cut on the {loc}"),
+            (WoundKind::Cut, Severity::Critical) => write!(f, "gaping wound on the {loc}"),
+            (WoundKind::Pierce, Severity::Minor) => write!(f, "puncture in the {loc}"),
+            (WoundKind::Pierce, Severity::Moderate) => write!(f, "stab wound in the {loc}"),
+            (WoundKind::Pierce, Severity::Serious) => write!(f, "deep stab wound in the {loc}"),
+            (WoundKind::Pierce, Severity::Critical) => write!(f, "impaled {loc}"),
+            (WoundKind::Crush, Severity::Minor) => write!(f, "bruised {loc}"),
+            (WoundKind::Crush, Severity::Moderate) => write!(f, "badly bruised {loc}"),
+            (WoundKind::Crush, Severity::Serious) => write!(f, "fractured {loc}"),
+            (WoundKind::Crush, Severity::Critical) => write!(f, "crushed {loc}"),
+            (WoundKind::Burn, Severity::Minor) => write!(f, "singed {loc}"),
+            (WoundKind::Burn, Severity::Moderate) => write!(f, "burn on the {loc}"),
+            (WoundKind::Burn, Severity::Serious) => write!(f, "severe burn on the {loc}"),
+            (WoundKind::Burn, Severity::Critical) => write!(f, "charred {loc}"),
+        }
+    }
+}
+
+/// A per-character list of [Wound]s.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Wounds<L = BodyLocation> {
+    wounds: Vec<Wound<L>>,
+}
+
+impl<L> Default for Wounds<L> {
+    fn default() -> Self {
+        Self { wounds: vec![] }
+    }
+}
+
+impl<L> Wounds<L> {
+    /// Make an empty wound list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a wound.
+    pub fn add(&mut self, wound: Wound<L>) {
+        self.wounds.push(wound)
+    }
+
+    /// Iterate through all the wounds, healed ones included.
+    pub fn iter(&self) -> impl Iterator<Item = &Wound<L>> {
+        self.wounds.iter()
+    }
+
+    /// Iterate (mutably) through all the wounds, healed ones included.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Wound<L>> {
+        self.wounds.iter_mut()
+    }
+
+    /// Iterate through wounds which aren't healed (yet).
+    pub fn active(&self) -> impl Iterator<Item = &Wound<L>> {
+        self.wounds.iter().filter(|w| !w.state.is_healed())
+    }
+
+    /// Iterate through wounds at the given `location`.
+    pub fn at<'a>(&'a self, location: &'a L) -> impl Iterator<Item = &'a Wound<L>>
+    where L: PartialEq {
+        self.wounds.iter().filter(move |w| w.location == *location)
+    }
+
+    /// Let `days` pass for all the wounds.
+    pub fn heal(&mut self, days: u32) {
+        self.wounds.iter_mut().for_each(|w| { w.heal(days); });
+    }
+
+    /// Forget wounds which healed without a trace.
+    pub fn prune(&mut self) {
+        self.wounds.retain(|w| w.state != WoundState::Healed)
+    }
+}
+
+impl<L: Display> Wounds<L> {
+    /// Summarize the active wounds, e.g. `"deep cut on the left arm and bruised chest"`.
+    pub fn summary(&self) -> String {
+        self.active().natural_join()
+    }
+}
+
+#[cfg(test)]
+mod wound_tests {
+    use crate::direction::bilateral::Bilateral;
+
+    use super::*;
+
+    #[test]
+    fn summary() {
+        let mut w = Wounds::new();
+        assert_eq!("", w.summary());
+        w.add(Wound::new(WoundKind::Cut, Severity::Serious, BodyLocation::Arm(Bilateral::Left)));
+        assert_eq!("deep cut on the left arm", w.summary());
+        w.add(Wound::new(WoundKind::Crush, Severity::Minor, BodyLocation::Chest));
+        assert_eq!("deep cut on the left arm and bruised chest", w.summary());
+    }
+
+    #[test]
+    fn minor_wounds_heal_untreated() {
+        let mut w = Wound::new(WoundKind::Burn, Severity::Minor, BodyLocation::Hand(Bilateral::Right));
+        assert_eq!(WoundState::Healing, w.heal(1));
+        assert_eq!(2, w.healing_days());
+        assert_eq!(WoundState::Healed, w.heal(5));
+        assert!(w.treat().is_err());
+    }
+
+    #[test]
+    fn serious_wounds_need_treatment_and_scar() {
+        let mut w = Wound::new(WoundKind::Pierce, Severity::Serious, BodyLocation::Abdomen);
+        assert_eq!(WoundState::Fresh, w.heal(10));
+        assert_eq!(30, w.healing_days());
+        w.treat().unwrap();
+        assert_eq!(WoundState::Treated, w.state());
+        assert!(w.treat().is_err());
+        assert_eq!(WoundState::Healing, w.heal(10));
+        assert_eq!(WoundState::Scarred, w.heal(20));
+        assert_eq!("scar on the abdomen", w.to_string());
+    }
+
+    #[test]
+    fn healed_wounds_drop_out_of_summary() {
+        let mut w = Wounds::new();
+        w.add(Wound::new(WoundKind::Crush, Severity::Minor, BodyLocation::Head));
+        w.add(Wound::new(WoundKind::Cut, Severity::Moderate, BodyLocation::Face));
+        w.heal(5);
+        assert_eq!("cut on the face", w.summary());
+        w.prune();
+        assert_eq!(1, w.iter().count());
+        assert_eq!(1, w.at(&BodyLocation::Face).count());
+    }
+}
@@ -0,0 +1,423 @@
+//! Checks - testing a [Stat], [Rank] or whatever against the dice.
+//!
+//! A [Check] comes in a few [flavors][CheckMode]:
+//! * target number - roll + score + modifier has to reach the target,
+//! * roll-under - roll has to be at most score + modifier,
+//! * roll-over - roll + modifier has to be at least the score,
+//!
+//! and any of them can be used for [opposed][Check::opposed] contests too.
+//!
+//! Margin is positive (or zero) on success and negative on failure, in every
+//! mode. Natural rolls within the critical [bands][Band] override the margin.
+//!
+//! ```
+//! use std::num::NonZeroU32;
+//! use rpgassist::{check::Check, details::DetailedDisplay, rng::SeededRng, stat::Stat};
+//!
+//! let check = Check::roll_under().with_degrees(NonZeroU32::new(3).unwrap());
+//! let result = check.roll_with(&Stat::Dex { val: 12 }, -2, &mut SeededRng::new(7));
+//! assert_eq!(result.outcome.is_success(), result.margin >= 0);
+//! println!("{}", result.detailed());
+//! ```
+use std::{fmt::Display, num::NonZeroU32};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{details::DetailedDisplay, dice::{DiceExpr, DiceRoll}, modifier::stack::Resolution, ranking::Rank, rng::{ChaosRng, DiceRng}, stat::Stat};
+
+/// Anything with a score which can be checked against.
+pub trait Checkable {
+    /// Get the score to check against.
+    fn score(&self) -> i32;
+    /// Get a label for the breakdown.
+    fn label(&self) -> String;
+}
+
+impl Checkable for Stat {
+    fn score(&self) -> i32 {
+        self.value()
+    }
+
+    fn label(&self) -> String {
+        self.stat_base().to_string()
+    }
+}
+
+impl Checkable for Rank {
+    fn score(&self) -> i32 {
+        self.value()
+    }
+
+    fn label(&self) -> String {
+        "rank".into()
+    }
+}
+
+impl Checkable for Resolution {
+    fn score(&self) -> i32 {
+        self.value
+    }
+
+    fn label(&self) -> String {
+        self.target.to_string()
+    }
+}
+
+impl Checkable for i32 {
+    fn score(&self) -> i32 {
+        *self
+    }
+
+    fn label(&self) -> String {
+        "score".into()
+    }
+}
+
+/// How a [Check] is resolved.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckMode {
+    /// Roll + score + modifier ≥ target number.
+    TargetNumber(i32),
+    /// Roll ≤ score + modifier.
+    RollUnder,
+    /// Roll + modifier ≥ score.
+    RollOver,
+}
+
+/// Inclusive range of natural rolls.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Band {
+    pub min: i32,
+    pub max: i32,
+}
+
+impl Band {
+    /// Is `natural` within the band?
+    pub fn contains(&self, natural: i32) -> bool {
+        (self.min..=self.max).contains(&natural)
+    }
+}
+
+/// Outcome of a [Check].
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Outcome {
+    CriticalFailure,
+    Failure,
+    Success,
+    CriticalSuccess,
+}
+
+impl Outcome {
+    /// Success, critical or not?
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success | Self::CriticalSuccess)
+    }
+
+    /// Critical, success or failure?
+    pub fn is_critical(&self) -> bool {
+        matches!(self, Self::CriticalSuccess | Self::CriticalFailure)
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CriticalFailure => write!(f, "critical failure"),
+            Self::Failure => write!(f, "failure"),
+            Self::Success => write!(f, "success"),
+            Self::CriticalSuccess => write!(f, "critical success"),
+        }
+    }
+}
+
+/// A check, i.e. dice, [mode][CheckMode], critical bands and degrees of success.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub dice: DiceExpr,
+    pub mode: CheckMode,
+    #[serde(default)]
+    pub crit_success: Option<Band>,
+    #[serde(default)]
+    pub crit_failure: Option<Band>,
+    /// Margin per extra degree of success/failure, if degrees are used at all.
+    #[serde(default)]
+    pub degree_step: Option<NonZeroU32>,
+}
+
+impl Check {
+    /// A check with the given `dice` and `mode`, no crits nor degrees.
+    pub fn new(dice: DiceExpr, mode: CheckMode) -> Self {
+        Self { dice, mode, crit_success: None, crit_failure: None, degree_step: None }
+    }
+
+    /// A `1d20` + score vs. `target` check.
+    pub fn target_number(target: i32) -> Self {
+        Self::new("1d20".parse().unwrap(), CheckMode::TargetNumber(target))
+    }
+
+    /// A `1d20` roll-under check.
+    pub fn roll_under() -> Self {
+        Self::new("1d20".parse().unwrap(), CheckMode::RollUnder)
+    }
+
+    /// A `1d20` roll-over check.
+    pub fn roll_over() -> Self {
+        Self::new("1d20".parse().unwrap(), CheckMode::RollOver)
+    }
+
+    /// Use some other dice.
+    pub fn with_dice(mut self, dice: DiceExpr) -> Self {
+        self.dice = dice;
+        self
+    }
+
+    /// Natural rolls from `min` to `max` (inclusive) always succeed, critically.
+    pub fn with_crit_success(mut self, min: i32, max: i32) -> Self {
+        self.crit_success = Some(Band { min, max });
+        self
+    }
+
+    /// Natural rolls from `min` to `max` (inclusive) always fail, critically.
+    pub fn with_crit_failure(mut self, min: i32, max: i32) -> Self {
+        self.crit_failure = Some(Band { min, max });
+        self
+    }
+
+    /// Count an extra degree of success/failure every `step` points of margin.
+    pub fn with_degrees(mut self, step: NonZeroU32) -> Self {
+        self.degree_step = Some(step);
+        self
+    }
+
+    /// Roll the check for `subject` with a situational `modifier`.
+    pub fn roll<S: Checkable + ?Sized>(&self, subject: &S, modifier: i32) -> CheckResult {
+        self.roll_with(subject, modifier, &mut ChaosRng)
+    }
+
+    /// Roll the check for `subject` with a situational `modifier`, using the given `rng`.
+    pub fn roll_with<S: Checkable + ?Sized, R: DiceRng + ?Sized>(&self, subject: &S, modifier: i32, rng: &mut R) -> CheckResult {
+        let roll = self.dice.roll_with(rng);
+        let result = self.resolve(subject, modifier, roll);
+        if rng.is_recording() {
+            rng.record(crate::audit::RollRecord::new("Check::roll", &self.dice, result.roll.raw(), result.outcome));
+        }
+        result
+    }
+
+    /// Resolve an already made `roll`.
+    pub fn resolve<S: Checkable + ?Sized>(&self, subject: &S, modifier: i32, roll: DiceRoll) -> CheckResult {
+        let (score, natural) = (subject.score(), roll.total());
+        // worked out in i64 and saturated at the i32 limits - extreme scores/targets mustn't overflow
+        let (n, s, m) = (natural as i64, score as i64, modifier as i64);
+        let (total, margin) = match self.mode {
+            CheckMode::TargetNumber(tn) => (n + s + m, n + s + m - tn as i64),
+            CheckMode::RollUnder => (n, s + m - n),
+            CheckMode::RollOver => (n + m, n + m - s),
+        };
+        let (total, margin) = (saturate(total), saturate(margin));
+        let outcome = match (self.crit_success, self.crit_failure) {
+            (Some(b), _) if b.contains(natural) => Outcome::CriticalSuccess,
+            (_, Some(b)) if b.contains(natural) => Outcome::CriticalFailure,
+            _ if margin >= 0 => Outcome::Success,
+            _ => Outcome::Failure
+        };
+        let degrees = match self.degree_step.map(|s| s.get() as i64) {
+            None => if outcome.is_success() {1} else {-1},
+            Some(step) if outcome.is_success() => saturate(1 + (margin as i64).max(0) / step),
+            Some(step) => saturate(-(1 + (-(margin as i64) - 1).max(0) / step)),
+        };
+        CheckResult { label: subject.label(), mode: self.mode, roll, natural, score, modifier, total, margin, outcome, degrees }
+    }
+
+    /// Roll an opposed contest between `a` and `b`.
+    pub fn opposed<A, B>(&self, a: &A, a_mod: i32, b: &B, b_mod: i32) -> ContestResult
+    where A: Checkable + ?Sized, B: Checkable + ?Sized {
+        self.opposed_with(a, a_mod, b, b_mod, &mut ChaosRng)
+    }
+
+    /// Roll an opposed contest between `a` and `b`, using the given `rng`.
+    ///
+    /// Better outcome wins, and if those are equal, the bigger margin does.
+    pub fn opposed_with<A, B, R>(&self, a: &A, a_mod: i32, b: &B, b_mod: i32, rng: &mut R) -> ContestResult
+    where A: Checkable + ?Sized, B: Checkable + ?Sized, R: DiceRng + ?Sized {
+        let a = self.roll_with(a, a_mod, rng);
+        let b = self.roll_with(b, b_mod, rng);
+        let winner = match (a.outcome, a.margin).cmp(&(b.outcome, b.margin)) {
+            std::cmp::Ordering::Greater => Winner::First,
+            std::cmp::Ordering::Less => Winner::Second,
+            std::cmp::Ordering::Equal => Winner::Tie,
+        };
+        ContestResult { a, b, winner }
+    }
+}
+
+/// Result of a [Check], with full breakdown.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct CheckResult {
+    /// What was checked, e.g. `"Dexterity"`.
+    pub label: String,
+    pub mode: CheckMode,
+    pub roll: DiceRoll,
+    /// Total of the dice alone.
+    pub natural: i32,
+    pub score: i32,
+    pub modifier: i32,
+    /// Roll + score + modifier for target number checks, roll + modifier for
+    /// roll-over and just the roll for roll-under checks.
+    pub total: i32,
+    pub margin: i32,
+    pub outcome: Outcome,
+    /// Degrees of success (positive) or failure (negative).
+    pub degrees: i32,
+}
+
+impl Display for CheckResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({:+})", self.outcome, self.margin)
+    }
+}
+
+impl DetailedDisplay for CheckResult {
+    fn detailed_display(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "roll:     {}", self.roll)?;
+        writeln!(f, "{:<9} {}", format!("{}:", self.label), self.score)?;
+        writeln!(f, "modifier: {:+}", self.modifier)?;
+        match self.mode {
+            CheckMode::TargetNumber(tn) => writeln!(f, "total:    {} vs. {tn}", self.total)?,
+            CheckMode::RollUnder => writeln!(f, "needed:   ≤{}", self.score.saturating_add(self.modifier))?,
+            CheckMode::RollOver => writeln!(f, "total:    {} vs. {}", self.total, self.score)?,
+        }
+        writeln!(f, "margin:   {:+}", self.margin)?;
+        write!(f, "{} ({} degree{})", self.outcome, self.degrees.abs(), if self.degrees.abs() == 1 {""} else {"s"})
+    }
+}
+
+/// Bring `value` within the `i32` limits.
+fn saturate(value: i64) -> i32 {
+    value.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+/// Who won a contest.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Winner {
+    First,
+    Second,
+    Tie,
+}
+
+/// Result of an [opposed][Check::opposed] contest.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct ContestResult {
+    pub a: CheckResult,
+    pub b: CheckResult,
+    pub winner: Winner,
+}
+
+impl Display for ContestResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.winner {
+            Winner::First => write!(f, "{} wins ({} vs. {})", self.a.label, self.a, self.b),
+            Winner::Second => write!(f, "{} wins ({} vs. {})", self.b.label, self.b, self.a),
+            Winner::Tie => write!(f, "tie ({} vs. {})", self.a, self.b),
+        }
+    }
+}
+
+impl DetailedDisplay for ContestResult {
+    fn detailed_display(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.a.detailed())?;
+        writeln!(f, "--")?;
+        writeln!(f, "{}", self.b.detailed())?;
+        write!(f, "=> {self}")
+    }
+}
+
+#[cfg(test)]
+mod check_tests {
+    use crate::{audit::AuditedRng, rng::SeededRng};
+
+    use super::*;
+
+    /// Constant "dice", for deterministic results.
+    fn roll_of(natural: i32) -> DiceRoll {
+        DiceExpr::from(natural).roll()
+    }
+
+    #[test]
+    fn target_number() {
+        let c = Check::target_number(15).with_degrees(NonZeroU32::new(5).unwrap());
+        let r = c.resolve(&Stat::Str { val: 3 }, 2, roll_of(10));
+        assert_eq!(15, r.total);
+        assert_eq!(0, r.margin);
+        assert_eq!(Outcome::Success, r.outcome);
+        assert_eq!(1, r.degrees);
+        let r = c.resolve(&Stat::Str { val: 3 }, 2, roll_of(20));
+        assert_eq!(3, r.degrees);
+        let r = c.resolve(&Stat::Str { val: 3 }, 2, roll_of(4));
+        assert_eq!(-6, r.margin);
+        assert_eq!(-2, r.degrees);
+        let r = Check::target_number(15).with_degrees(NonZeroU32::MAX).resolve(&Stat::Str { val: 3 }, 2, roll_of(20));
+        assert_eq!(1, r.degrees);
+        let json = r#"{ "dice": "1d20", "mode": "rollunder", "degree_step": 0 }"#;
+        assert!(serde_json::from_str::<Check>(json).is_err());
+        assert!(serde_json::from_str::<Check>(&json.replace(": 0", ": 3")).is_ok());
+    }
+
+    #[test]
+    fn extremes_saturate() {
+        let r = Check::target_number(i32::MIN).resolve(&0, 0, roll_of(0));
+        assert_eq!(i32::MAX, r.margin);
+        assert_eq!(Outcome::Success, r.outcome);
+        let r = Check::target_number(i32::MAX).with_degrees(NonZeroU32::MIN).resolve(&i32::MIN, i32::MIN, roll_of(1));
+        assert_eq!((i32::MIN, i32::MIN), (r.total, r.margin));
+        assert_eq!(i32::MIN, r.degrees);
+        let r = Check::roll_under().with_degrees(NonZeroU32::MIN).resolve(&i32::MAX, i32::MAX, roll_of(1));
+        assert_eq!(i32::MAX, r.margin);
+        assert_eq!(i32::MAX, r.degrees);
+        assert!(r.detailed().to_string().contains(&format!("≤{}", i32::MAX)));
+        let r = Check::roll_over().resolve(&i32::MIN, i32::MAX, roll_of(20));
+        assert_eq!(i32::MAX, r.margin);
+    }
+
+    #[test]
+    fn roll_under_and_over() {
+        let under = Check::roll_under();
+        assert!(under.resolve(&Rank::AVERAGE, 0, roll_of(3)).outcome.is_success());
+        assert!(!under.resolve(&Rank::AVERAGE, 0, roll_of(4)).outcome.is_success());
+        assert!(under.resolve(&Rank::AVERAGE, 1, roll_of(4)).outcome.is_success());
+        let over = Check::roll_over();
+        assert_eq!(2, over.resolve(&12, 0, roll_of(14)).margin);
+        assert_eq!(-1, over.resolve(&12, 0, roll_of(11)).margin);
+        assert_eq!(1, over.resolve(&12, 2, roll_of(11)).margin);
+    }
+
+    #[test]
+    fn crits_override() {
+        let c = Check::roll_under().with_crit_success(1, 1).with_crit_failure(19, 20);
+        assert_eq!(Outcome::CriticalSuccess, c.resolve(&-5, 0, roll_of(1)).outcome);
+        assert_eq!(Outcome::CriticalFailure, c.resolve(&50, 0, roll_of(19)).outcome);
+        assert_eq!(Outcome::Success, c.resolve(&50, 0, roll_of(18)).outcome);
+    }
+
+    #[test]
+    fn opposed() {
+        let c = Check::target_number(0);
+        let mut rng = AuditedRng::new(SeededRng::new(42));
+        let r = c.opposed_with(&Stat::Str { val: 100 }, 0, &Stat::Str { val: 0 }, 0, &mut rng);
+        assert_eq!(Winner::First, r.winner);
+        assert_eq!(2, rng.records().len());
+        let r = c.opposed_with(&5, 0, &5, 0, &mut SeededRng::new(1));
+        assert_eq!(r.winner == Winner::Tie, r.a.margin == r.b.margin);
+    }
+
+    #[test]
+    fn breakdown() {
+        let c = Check::target_number(12);
+        let r = c.resolve(&Stat::Dex { val: 4 }, -1, roll_of(10));
+        assert_eq!("success (+1)", r.to_string());
+        let details = r.detailed().to_string();
+        assert!(details.contains("total:    13 vs. 12"));
+        assert!(details.ends_with("success (1 degree)"));
+    }
+}
@@ -5,11 +5,28 @@
 //! 
 //! See: [DetailedDisplay], [ProperCaseExt], [NewsPaperCaseExt]
 //! 
+use std::fmt::Display;
+
 /// A trait akin to `.to_string()` etc., but meant for delivery of more
 /// detailed information, leaving the other functions to deal with simpler
 /// things.
 pub trait DetailedDisplay {
     fn detailed_display(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result;
+
+    /// Wrap self into something `format!()` & co. can deal with, e.g.
+    /// `format!("{}", rank.detailed())`.
+    fn detailed(&self) -> Detailed<'_, Self> {
+        Detailed(self)
+    }
+}
+
+/// A [Display] wrapper for anything [DetailedDisplay].
+pub struct Detailed<'a, T: DetailedDisplay + ?Sized>(pub &'a T);
+
+impl<T: DetailedDisplay + ?Sized> Display for Detailed<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.detailed_display(f)
+    }
 }
 
 /// An extension trait for converting strings into (semi-)"Proper Case."
@@ -23,7 +40,7 @@ pub trait DetailedDisplay {
 /// # Example
 ///
 /// ```
-/// use rpgassist::ext::ProperCaseExt; // (Adjust path as needed)
+/// use rpgassist::details::ProperCaseExt;
 ///
 /// let s = "a test-string (with fawns)";
 /// assert_eq!(s.proper_case(), "A Test-String (With Fawns)");
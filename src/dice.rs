@@ -0,0 +1,124 @@
+//! Dice specs, e.g. `3d6`, and [dice expressions][DiceExpr], e.g. `4d6kh3+2`.
+//! 
+//! Both [Dice] and [DiceExpr] (de)serialize as plain strings.
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::rng::{ChaosRng, DiceRng};
+
+mod expr;
+pub use expr::{DiceExpr, DiceRoll, DiceTerm, DieRoll, Keep, Term, TermRoll};
+
+/// Most dice a single parsed term may have.
+pub const MAX_COUNT: u32 = 1_000;
+/// Most sides a parsed die may have.
+pub const MAX_SIDES: u32 = 10_000;
+/// How many times a single die may explode before we call it a day - both
+/// when rolling and when [computing odds][crate::analysis::Distribution].
+pub const MAX_EXPLOSIONS: u32 = 10;
+
+/// Plain `NdX` dice spec - `count` dice of `sides` sides each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Dice {
+    pub count: u32,
+    pub sides: u32,
+}
+
+impl Dice {
+    /// Make a new `count`d`sides` spec.
+    pub const fn new(count: u32, sides: u32) -> Self {
+        Self { count, sides }
+    }
+
+    /// Lowest possible result.
+    pub fn min(&self) -> i32 {
+        self.count as i32
+    }
+
+    /// Highest possible result.
+    pub fn max(&self) -> i32 {
+        (self.count * self.sides) as i32
+    }
+
+    /// Roll the dice.
+    pub fn roll(&self) -> i32 {
+        self.roll_with(&mut ChaosRng)
+    }
+
+    /// Roll the dice using the given `rng`.
+    pub fn roll_with<R: DiceRng + ?Sized>(&self, rng: &mut R) -> i32 {
+        rng.roll(self.count, self.sides)
+    }
+}
+
+impl Display for Dice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}d{}", self.count, self.sides)
+    }
+}
+
+/// Error for dice strings that just don't parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDiceError(pub String);
+
+impl Display for ParseDiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cannot parse dice from '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseDiceError {}
+
+impl FromStr for Dice {
+    type Err = ParseDiceError;
+    /// Parse `"NdX"` or `"dX"` (which equals `"1dX"`).
+    ///
+    /// Counts over [MAX_COUNT] and sides over [MAX_SIDES] don't parse.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseDiceError(s.to_string());
+        let (count, sides) = s.trim().to_lowercase().split_once('d')
+            .map(|(c, x)| (c.to_string(), x.to_string()))
+            .ok_or_else(err)?;
+        let count = if count.is_empty() { 1 } else { count.parse().map_err(|_| err())? };
+        let sides = sides.parse().map_err(|_| err())?;
+        if count == 0 || sides == 0 || count > MAX_COUNT || sides > MAX_SIDES {
+            return Err(err())
+        }
+        Ok(Self { count, sides })
+    }
+}
+
+impl Serialize for Dice {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Dice {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: Deserializer<'de> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod dice_tests {
+    use super::*;
+
+    #[test]
+    fn parse_dice() {
+        assert_eq!(Dice::new(3, 6), "3d6".parse().unwrap());
+        assert_eq!(Dice::new(1, 20), "d20".parse().unwrap());
+        assert_eq!(Dice::new(2, 10), " 2D10 ".parse().unwrap());
+    }
+
+    #[test]
+    fn parse_bad_dice() {
+        assert!("3x6".parse::<Dice>().is_err());
+        assert!("0d6".parse::<Dice>().is_err());
+        assert!("3d".parse::<Dice>().is_err());
+    }
+}
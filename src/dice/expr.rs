@@ -0,0 +1,460 @@
+//! Dice expressions, e.g. `3d6+2`, `4d6kh3`, `2d10-1d4`, `d%` or `3d6!`.
+//!
+//! # Syntax
+//!
+//! Terms are summed/subtracted with `+` and `-`. Each term is either a
+//! constant or `[count]d<sides|%>` followed by any of:
+//!
+//! * `!` - exploding dice; a max roll rolls again and adds up.
+//! * `khN`/`kN` - keep the highest `N` dice, `klN` - keep the lowest `N`.
+//! * `dhN` - drop the highest `N` dice, `dlN` - drop the lowest `N`.
+//!
+//! ```
+//! use rpgassist::{dice::DiceExpr, rng::SeededRng};
+//!
+//! let expr: DiceExpr = "4d6kh3+2".parse().unwrap();
+//! let roll = expr.roll_with(&mut SeededRng::new(3));
+//! assert!((5..=20).contains(&roll.total()));
+//! assert_eq!("4d6kh3+2", expr.to_string());
+//! ```
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{analysis::{Distribution, DistributionError}, dice::{Dice, MAX_COUNT, MAX_EXPLOSIONS, MAX_SIDES, ParseDiceError}, rng::{ChaosRng, DiceRng}};
+
+/// Which dice of a [DiceTerm] count towards the total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Keep {
+    Highest(u32),
+    Lowest(u32),
+}
+
+/// A `NdX` term with optional explosions and keep rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DiceTerm {
+    pub dice: Dice,
+    pub keep: Option<Keep>,
+    pub explode: bool,
+}
+
+/// A single (signed) term of a [DiceExpr].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Term {
+    Dice(DiceTerm),
+    Const(i32),
+}
+
+/// A parsed dice expression.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DiceExpr {
+    /// Terms and whether they get subtracted (`true`) or added (`false`).
+    terms: Vec<(bool, Term)>,
+}
+
+/// A single die within a [DiceRoll] breakdown.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DieRoll {
+    /// The raw roll(s) - more than one if the die exploded.
+    pub rolls: Vec<u32>,
+    /// Did the die count towards the total?
+    pub kept: bool,
+}
+
+impl DieRoll {
+    /// Get the value of the die, explosions included.
+    pub fn value(&self) -> i32 {
+        self.rolls.iter().sum::<u32>() as i32
+    }
+}
+
+/// Per-term breakdown of a [DiceRoll].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum TermRoll {
+    Dice { negative: bool, dice: Vec<DieRoll> },
+    Const { negative: bool, value: i32 },
+}
+
+impl TermRoll {
+    /// Get the (signed) subtotal of the term, saturating at the `i32` limits.
+    pub fn subtotal(&self) -> i32 {
+        let (negative, value) = match self {
+            Self::Dice { negative, dice } => (negative, dice.iter().filter(|d| d.kept).fold(0i32, |t, d| t.saturating_add(d.value()))),
+            Self::Const { negative, value } => (negative, *value),
+        };
+        if *negative { value.saturating_neg() } else { value }
+    }
+}
+
+/// Result of rolling a [DiceExpr], breakdown included.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiceRoll {
+    terms: Vec<TermRoll>,
+}
+
+impl DiceRoll {
+    /// Get the total, saturating at the `i32` limits.
+    pub fn total(&self) -> i32 {
+        self.terms.iter().fold(0, |t, term| t.saturating_add(term.subtotal()))
+    }
+
+    /// Get the per-term breakdown.
+    pub fn terms(&self) -> &[TermRoll] {
+        &self.terms
+    }
+
+    /// Get all the raw die faces rolled, in order.
+    pub fn raw(&self) -> Vec<u32> {
+        self.terms.iter().flat_map(|t| match t {
+            TermRoll::Dice { dice, .. } => dice.iter().flat_map(|d| d.rolls.clone()).collect(),
+            TermRoll::Const { .. } => vec![],
+        }).collect()
+    }
+}
+
+impl DiceTerm {
+    /// Lowest possible result.
+    pub fn min(&self) -> i32 {
+        match self.keep {
+            Some(Keep::Highest(n) | Keep::Lowest(n)) => n.min(self.dice.count) as i32,
+            None => self.dice.min(),
+        }
+    }
+
+    /// Highest possible result, if bounded (exploding dice aren't).
+    pub fn max(&self) -> Option<i32> {
+        if self.explode { return None }
+        Some(match self.keep {
+            Some(Keep::Highest(n) | Keep::Lowest(n)) => (n.min(self.dice.count) * self.dice.sides) as i32,
+            None => self.dice.max(),
+        })
+    }
+
+    /// Roll the term using the given `rng`.
+    fn roll_with<R: DiceRng + ?Sized>(&self, rng: &mut R) -> Vec<DieRoll> {
+        let mut dice: Vec<DieRoll> = (0..self.dice.count).map(|_| {
+            let mut rolls = vec![rng.roll_die(self.dice.sides)];
+            while self.explode && *rolls.last().unwrap() == self.dice.sides && rolls.len() <= MAX_EXPLOSIONS as usize {
+                rolls.push(rng.roll_die(self.dice.sides));
+            }
+            DieRoll { rolls, kept: true }
+        }).collect();
+
+        if let Some(keep) = self.keep {
+            let mut order: Vec<usize> = (0..dice.len()).collect();
+            order.sort_by_key(|i| dice[*i].value());
+            let dropped = match keep {
+                Keep::Highest(n) => &order[..order.len().saturating_sub(n as usize)],
+                Keep::Lowest(n) => &order[(n as usize).min(order.len())..],
+            };
+            for i in dropped {
+                dice[*i].kept = false;
+            }
+        }
+        dice
+    }
+}
+
+impl DiceExpr {
+    /// Get the terms, each with a flag telling if it gets subtracted.
+    pub fn terms(&self) -> &[(bool, Term)] {
+        &self.terms
+    }
+
+    /// Get the number of sides if the expression is just a single plain die, `1dX`.
+    pub fn single_die(&self) -> Option<u32> {
+        match self.terms.as_slice() {
+            [(false, Term::Dice(DiceTerm { dice: Dice { count: 1, sides }, keep: None, explode: false }))] => Some(*sides),
+            _ => None
+        }
+    }
+
+    /// Lowest possible result, if bounded (subtracted exploding dice aren't).
+    /// Saturates at the `i32` limits, like [DiceRoll::total].
+    pub fn min(&self) -> Option<i32> {
+        self.terms.iter().try_fold(0i32, |total, (neg, t)| Some(total.saturating_add(match (neg, t) {
+            (false, Term::Const(v)) => *v,
+            (true, Term::Const(v)) => v.saturating_neg(),
+            (false, Term::Dice(d)) => d.min(),
+            (true, Term::Dice(d)) => d.max()?.saturating_neg(),
+        })))
+    }
+
+    /// Highest possible result, if bounded (exploding dice aren't).
+    /// Saturates at the `i32` limits, like [DiceRoll::total].
+    pub fn max(&self) -> Option<i32> {
+        self.terms.iter().try_fold(0i32, |total, (neg, t)| Some(total.saturating_add(match (neg, t) {
+            (false, Term::Const(v)) => *v,
+            (true, Term::Const(v)) => v.saturating_neg(),
+            (false, Term::Dice(d)) => d.max()?,
+            (true, Term::Dice(d)) => d.min().saturating_neg(),
+        })))
+    }
+
+    /// Get the exact outcome [Distribution] of the expression.
+    ///
+    /// # Returns
+    /// **a)** the distribution, or
+    /// **b)** [DistributionError::TooComplex] if a term would take more than
+    /// [MAX_EXACT_WORK][crate::analysis::MAX_EXACT_WORK] to work out.
+    pub fn distribution(&self) -> Result<Distribution, DistributionError> {
+        Distribution::try_from(self)
+    }
+
+    /// Roll the expression.
+    pub fn roll(&self) -> DiceRoll {
+        self.roll_with(&mut ChaosRng)
+    }
+
+    /// Roll the expression using the given `rng`.
+    pub fn roll_with<R: DiceRng + ?Sized>(&self, rng: &mut R) -> DiceRoll {
+        DiceRoll { terms: self.terms.iter().map(|(negative, t)| match t {
+            Term::Dice(d) => TermRoll::Dice { negative: *negative, dice: d.roll_with(rng) },
+            Term::Const(value) => TermRoll::Const { negative: *negative, value: *value },
+        }).collect() }
+    }
+}
+
+impl From<Dice> for DiceExpr {
+    fn from(dice: Dice) -> Self {
+        Self { terms: vec![(false, Term::Dice(DiceTerm { dice, keep: None, explode: false }))] }
+    }
+}
+
+impl From<i32> for DiceExpr {
+    fn from(value: i32) -> Self {
+        let mut terms = vec![(value < 0, Term::Const(value.saturating_abs()))];
+        if value == i32::MIN {
+            // |i32::MIN| doesn't fit an i32, so the last 1 goes into a term of its own
+            terms.push((true, Term::Const(1)));
+        }
+        Self { terms }
+    }
+}
+
+/// Parse a single unsigned term, e.g. `4d6kh3` or `2`.
+fn parse_term(s: &str, orig: &str) -> Result<Term, ParseDiceError> {
+    let err = || ParseDiceError(orig.to_string());
+    let Some((count, rest)) = s.split_once('d') else {
+        return s.parse().map(Term::Const).map_err(|_| err())
+    };
+    let count: u32 = if count.is_empty() { 1 } else { count.parse().map_err(|_| err())? };
+
+    let (sides, mut rest) = match rest.strip_prefix('%') {
+        Some(rest) => (100, rest),
+        None => {
+            let n = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            (rest[..n].parse().map_err(|_| err())?, &rest[n..])
+        }
+    };
+    if count == 0 || sides == 0 || count > MAX_COUNT || sides > MAX_SIDES { return Err(err()) }
+
+    let mut term = DiceTerm { dice: Dice::new(count, sides), keep: None, explode: false };
+    while !rest.is_empty() {
+        if let Some(r) = rest.strip_prefix('!') {
+            if term.explode || sides < 2 { return Err(err()) }
+            term.explode = true;
+            rest = r;
+            continue;
+        }
+        let (op, r) = ["kh", "kl", "dh", "dl", "k"].iter()
+            .find_map(|op| rest.strip_prefix(op).map(|r| (*op, r)))
+            .ok_or_else(err)?;
+        let n = r.find(|c: char| !c.is_ascii_digit()).unwrap_or(r.len());
+        let v: u32 = r[..n].parse().map_err(|_| err())?;
+        if term.keep.is_some() || v > count || (v == 0 && op.starts_with('k')) || (v == count && op.starts_with('d')) {
+            return Err(err())
+        }
+        term.keep = Some(match op {
+            "kh" | "k" => Keep::Highest(v),
+            "kl" => Keep::Lowest(v),
+            "dh" => Keep::Lowest(count - v),
+            _ => Keep::Highest(count - v),
+        });
+        rest = &r[n..];
+    }
+    Ok(Term::Dice(term))
+}
+
+impl FromStr for DiceExpr {
+    type Err = ParseDiceError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let compact: String = s.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+        let mut terms = vec![];
+        let mut negative = false;
+        let mut start = 0;
+        for (i, c) in compact.char_indices() {
+            if c == '+' || c == '-' {
+                if i > start {
+                    terms.push((negative, parse_term(&compact[start..i], s)?));
+                } else if i > 0 {
+                    // two operators in a row…
+                    return Err(ParseDiceError(s.to_string()))
+                }
+                negative = c == '-';
+                start = i + 1;
+            }
+        }
+        if start >= compact.len() {
+            return Err(ParseDiceError(s.to_string()))
+        }
+        terms.push((negative, parse_term(&compact[start..], s)?));
+        Ok(Self { terms })
+    }
+}
+
+impl Display for DiceTerm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.dice, if self.explode {"!"} else {""})?;
+        match self.keep {
+            Some(Keep::Highest(n)) => write!(f, "kh{n}"),
+            Some(Keep::Lowest(n)) => write!(f, "kl{n}"),
+            None => Ok(())
+        }
+    }
+}
+
+impl Display for DiceExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (negative, t)) in self.terms.iter().enumerate() {
+            if *negative { write!(f, "-")? } else if i > 0 { write!(f, "+")? }
+            match t {
+                Term::Dice(d) => write!(f, "{d}")?,
+                Term::Const(v) => write!(f, "{v}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for DiceRoll {
+    /// E.g. `[6, 5, 3, ~1~] +2 = 16`; exploded dice show up as `6+6+2`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, t) in self.terms.iter().enumerate() {
+            if i > 0 { write!(f, " ")? }
+            match t {
+                TermRoll::Dice { negative, dice } => {
+                    let dice: Vec<String> = dice.iter().map(|d| {
+                        let v = d.rolls.iter().map(|r| r.to_string()).collect::<Vec<_>>().join("+");
+                        if d.kept { v } else { format!("~{v}~") }
+                    }).collect();
+                    write!(f, "{}[{}]", if *negative {"-"} else if i > 0 {"+"} else {""}, dice.join(", "))?
+                },
+                TermRoll::Const { negative, value } => write!(f, "{}{value}", if *negative {"-"} else {"+"})?,
+            }
+        }
+        write!(f, " = {}", self.total())
+    }
+}
+
+impl Serialize for DiceExpr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for DiceExpr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: Deserializer<'de> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod dice_expr_tests {
+    use crate::rng::SeededRng;
+
+    use super::*;
+
+    fn parse(s: &str) -> DiceExpr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_and_display_round_trip() {
+        for s in ["3d6+2", "4d6kh3", "2d10-1d4", "3d6!", "1d20", "-2+1d4", "4d6kl1"] {
+            assert_eq!(s, parse(s).to_string());
+        }
+        assert_eq!("1d100", parse("d%").to_string());
+        assert_eq!("4d6kh3", parse("4d6dl1").to_string());
+        assert_eq!("4d6kh3", parse("4d6k3").to_string());
+        assert_eq!("3d6+2", parse(" 3D6 + 2 ").to_string());
+    }
+
+    #[test]
+    fn parse_errors() {
+        for s in ["", "3d", "d", "3x6", "3d6+", "3d6++2", "0d6", "4d6kh5", "4d6kh0", "1d1!", "3d6!!", "3d6kh2kl1", "1001d6", "1d10001", "99999999999d6"] {
+            assert!(s.parse::<DiceExpr>().is_err(), "'{s}' should not parse");
+        }
+    }
+
+    #[test]
+    fn constants() {
+        assert_eq!(-7, DiceExpr::from(-7).roll().total());
+        assert_eq!(i32::MIN, DiceExpr::from(i32::MIN).roll().total());
+        assert_eq!(i32::MAX, DiceExpr::from(i32::MAX).roll().total());
+        let big = parse("2147483647+1d6");
+        assert_eq!(i32::MAX, big.roll().total());
+        assert_eq!((Some(i32::MAX), Some(i32::MAX)), (big.min(), big.max()));
+        assert_eq!(i32::MIN, parse("0-2147483647-1000d10000").roll().total());
+        assert_eq!(i32::MAX, big.distribution().unwrap().max());
+    }
+
+    #[test]
+    fn explosions_are_capped() {
+        // an "rng" which always rolls max
+        struct Max;
+        impl DiceRng for Max {
+            fn roll_die(&mut self, sides: u32) -> u32 { sides }
+        }
+        let roll = parse("1d6!").roll_with(&mut Max);
+        assert_eq!(MAX_EXPLOSIONS as usize + 1, roll.raw().len());
+    }
+
+    #[test]
+    fn min_max() {
+        assert_eq!((Some(5), Some(20)), (parse("3d6+2").min(), parse("3d6+2").max()));
+        assert_eq!((Some(3), Some(18)), (parse("4d6kh3").min(), parse("4d6kh3").max()));
+        assert_eq!((Some(-2), Some(19)), (parse("2d10-1d4").min(), parse("2d10-1d4").max()));
+        assert_eq!(None, parse("3d6!").max());
+        assert_eq!(None, parse("1d4-3d6!").min());
+    }
+
+    #[test]
+    fn keep_highest_drops_lowest() {
+        let expr = parse("4d6kh3");
+        let mut rng = SeededRng::new(5);
+        for _ in 0..100 {
+            let roll = expr.roll_with(&mut rng);
+            let TermRoll::Dice { dice, .. } = &roll.terms()[0] else { panic!() };
+            assert_eq!(4, dice.len());
+            let dropped: Vec<&DieRoll> = dice.iter().filter(|d| !d.kept).collect();
+            assert_eq!(1, dropped.len());
+            assert!(dice.iter().all(|d| d.value() >= dropped[0].value()));
+            assert_eq!(roll.total(), dice.iter().map(|d| d.value()).sum::<i32>() - dropped[0].value());
+        }
+    }
+
+    #[test]
+    fn exploding_dice_explode() {
+        let expr = parse("1d2!");
+        let mut rng = SeededRng::new(6);
+        let rolls: Vec<DiceRoll> = (0..100).map(|_| expr.roll_with(&mut rng)).collect();
+        assert!(rolls.iter().any(|r| r.total() > 2));
+        for r in rolls {
+            let raw = r.raw();
+            assert!(raw[..raw.len() - 1].iter().all(|v| *v == 2));
+            assert_eq!(1, *raw.last().unwrap());
+        }
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let expr = parse("2d10-1d4+3");
+        let json = serde_json::to_string(&expr).unwrap();
+        assert_eq!("\"2d10-1d4+3\"", json);
+        assert_eq!(expr, serde_json::from_str(&json).unwrap());
+    }
+}
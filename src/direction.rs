@@ -1 +1,5 @@
-pub mod bilateral;
\ No newline at end of file
+pub mod arc;
+pub mod bilateral;
+pub mod compass;
+pub mod relative;
+pub mod vertical;
\ No newline at end of file
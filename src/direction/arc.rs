@@ -0,0 +1,157 @@
+//! Attack arcs - which side of a defender an attack comes from.
+//!
+//! The arc is a [Bilateral]: [front][Bilateral::Front] (front, front-left and
+//! front-right), [left][Bilateral::Left], [right][Bilateral::Right] or
+//! [back][Bilateral::Back] (rear, rear-left and rear-right).
+//!
+//! ```
+//! use rpgassist::{direction::{arc::AttackArc, bilateral::Bilateral, compass::Compass}, grid::square::Square, modifier::HasModifier};
+//!
+//! let defender = Square::new(0, 0);
+//! // defender faces north, attacker sneaks in from the south…
+//! let arc = defender.arc_of(Compass::North, &Square::new(0, 2)).unwrap();
+//! assert_eq!(Bilateral::Back, arc.arc);
+//! assert_eq!(2, arc.modifier());
+//! ```
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{body::location::BodyLocation, direction::{bilateral::Bilateral, compass::Compass, relative::Relative}, modifier::HasModifier, rng::{ChaosRng, DiceRng}, table::RollTable};
+
+/// The arc of a defender an attack comes from.
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, Copy, PartialEq, Eq)]
+pub struct AttackArc {
+    /// Which arc.
+    pub arc: Bilateral,
+    /// Exact direction the attack comes from, relative to the defender's facing.
+    pub relative: Relative,
+}
+
+impl AttackArc {
+    /// Get the arc of an attack coming from the given `relative` direction.
+    pub fn from_relative(relative: Relative) -> Self {
+        let arc = match relative {
+            Relative::FrontLeft | Relative::Front | Relative::FrontRight => Bilateral::Front,
+            Relative::Left => Bilateral::Left,
+            Relative::Right => Bilateral::Right,
+            Relative::RearLeft | Relative::Rear | Relative::RearRight => Bilateral::Back,
+        };
+        Self { arc, relative }
+    }
+
+    /// Get the arc of an attack coming from absolute direction `from`, with the defender facing `facing`.
+    pub fn from_direction(from: Compass, facing: Compass) -> Self {
+        Self::from_relative(from.relative_to(facing))
+    }
+
+    /// Get the arc of an attack coming from compass `bearing` (degrees clockwise
+    /// from north), with the defender facing `facing`.
+    pub fn from_bearing(bearing: f64, facing: Compass) -> Self {
+        Self::from_relative(Relative::from_degrees(bearing - facing.degrees()))
+    }
+
+    /// Is the attack coming from a flank (left or right)?
+    pub fn is_flank(&self) -> bool {
+        matches!(self.arc, Bilateral::Left | Bilateral::Right)
+    }
+
+    /// Is the attack coming from behind?
+    pub fn is_rear(&self) -> bool {
+        self.arc == Bilateral::Back
+    }
+
+    /// Relative weight of `location` being hit from this arc.
+    ///
+    /// * flanks: near side ×3, far side ×1, everything else ×2.
+    /// * front: nothing on the back gets hit.
+    /// * rear: nothing on the front gets hit.
+    pub fn weight(&self, location: &BodyLocation) -> u32 {
+        let side = location.side();
+        match self.arc {
+            Bilateral::Left | Bilateral::Right => match side {
+                Some(s) if s == self.arc => 3,
+                Some(s) if s == self.arc.opposite() => 1,
+                _ => 2
+            },
+            _ => (side != Some(self.arc.opposite())) as u32
+        }
+    }
+
+    /// Get the hit location table as seen from this arc.
+    pub fn hit_table(&self) -> RollTable<BodyLocation> {
+        BodyLocation::table()
+            .reweight(|l| self.weight(l))
+            .expect("Arc left nothing to hit?!")
+    }
+
+    /// Generate a random hit location for an attack from this arc.
+    pub fn random_location(&self) -> BodyLocation {
+        self.random_location_with(&mut ChaosRng)
+    }
+
+    /// Generate a random hit location for an attack from this arc using the given `rng`.
+    pub fn random_location_with<R: DiceRng + ?Sized>(&self, rng: &mut R) -> BodyLocation {
+        self.hit_table().roll_recorded_with(rng)
+    }
+}
+
+impl HasModifier for AttackArc {
+    /// Attacker's bonus: +1 from a flank, +2 from behind.
+    fn modifier(&self) -> i32 {
+        match self.arc {
+            Bilateral::Front => 0,
+            Bilateral::Left | Bilateral::Right => 1,
+            Bilateral::Back => 2,
+        }
+    }
+}
+
+impl Display for AttackArc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} arc", self.arc)
+    }
+}
+
+#[cfg(test)]
+mod arc_tests {
+    use crate::{direction::bilateral::Bilateral, grid::{hex::Hex, square::Square}};
+
+    use super::*;
+
+    #[test]
+    fn arcs_from_grid_positions() {
+        let d = Square::new(0, 0);
+        assert_eq!(Bilateral::Front, d.arc_of(Compass::North, &Square::new(1, -1)).unwrap().arc);
+        assert_eq!(Bilateral::Left, d.arc_of(Compass::North, &Square::new(-3, 0)).unwrap().arc);
+        assert_eq!(Bilateral::Right, d.arc_of(Compass::West, &Square::new(0, -1)).unwrap().arc);
+        assert!(d.arc_of(Compass::North, &d).is_none());
+        let h = Hex::new(0, 0);
+        assert_eq!(Bilateral::Back, h.arc_of(Compass::North, &Hex::new(0, 1)).unwrap().arc);
+        assert_eq!(Relative::FrontRight, h.arc_of(Compass::North, &Hex::new(1, -1)).unwrap().relative);
+    }
+
+    #[test]
+    fn left_arc_favors_left() {
+        let odds = AttackArc::from_relative(Relative::Left).hit_table().odds();
+        let left = odds.probability(&BodyLocation::Arm(Bilateral::Left));
+        let right = odds.probability(&BodyLocation::Arm(Bilateral::Right));
+        assert!((left - 3.0 * right).abs() < 1e-9);
+    }
+
+    #[test]
+    fn front_and_rear_exclude_far_side() {
+        let front = AttackArc::from_relative(Relative::Front).hit_table().odds();
+        assert_eq!(0.0, front.probability(&BodyLocation::Back));
+        let rear = AttackArc::from_relative(Relative::RearLeft).hit_table().odds();
+        assert_eq!(0.0, rear.probability(&BodyLocation::Face));
+        assert!(rear.probability(&BodyLocation::Buttocks) > 0.0);
+    }
+
+    #[test]
+    fn modifiers() {
+        assert_eq!(0, AttackArc::from_relative(Relative::FrontLeft).modifier());
+        assert_eq!(1, AttackArc::from_direction(Compass::East, Compass::North).modifier());
+        assert_eq!(2, AttackArc::from_direction(Compass::North, Compass::South).modifier());
+    }
+}
@@ -1,31 +1,70 @@
 //! Bilateralisms - left/right, front/back, etc.
 use std::fmt::Display;
 
-use dicebag::DiceExt;
 use serde::{Deserialize, Serialize};
 
+use crate::{audit::RollRecord, rng::{ChaosRng, DiceRng}};
+
 /// Some bilateralisms…
-#[derive(Debug, Deserialize, Serialize, Hash, Clone)]
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, Copy, PartialEq, Eq)]
 pub enum Bilateral {
     Left, Right,
     Front, Back,
 }
 
 impl Bilateral {
+    /// Get the opposite side.
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+            Self::Front => Self::Back,
+            Self::Back => Self::Front,
+        }
+    }
+
+    /// Mirror left ↔ right; front and back stay as they are.
+    pub fn mirror(&self) -> Self {
+        match self {
+            Self::Left | Self::Right => self.opposite(),
+            _ => *self
+        }
+    }
+
     /// Generate left/right randomly.
     pub fn random_lr() -> Self {
-        match 1.d2() {
+        Self::random_lr_with(&mut ChaosRng)
+    }
+
+    /// Generate left/right randomly using the given `rng`.
+    pub fn random_lr_with<R: DiceRng + ?Sized>(rng: &mut R) -> Self {
+        let roll = rng.roll_die(2);
+        let side = match roll {
             1 => Self::Left,
             _ => Self::Right
+        };
+        if rng.is_recording() {
+            rng.record(RollRecord::new("Bilateral::random_lr", "1d2", vec![roll], side));
         }
+        side
     }
 
     /// Generate front/back randomly.
     pub fn random_fb() -> Self {
-        match 1.d2() {
+        Self::random_fb_with(&mut ChaosRng)
+    }
+
+    /// Generate front/back randomly using the given `rng`.
+    pub fn random_fb_with<R: DiceRng + ?Sized>(rng: &mut R) -> Self {
+        let roll = rng.roll_die(2);
+        let side = match roll {
             1 => Self::Front,
             _ => Self::Back
+        };
+        if rng.is_recording() {
+            rng.record(RollRecord::new("Bilateral::random_fb", "1d2", vec![roll], side));
         }
+        side
     }
 }
 
@@ -0,0 +1,181 @@
+//! Compass directions - sixteen points of them, eight "main" ones.
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{audit::RollRecord, direction::relative::Relative, rng::{ChaosRng, DiceRng}};
+
+/// Sixteen-point compass directions, clockwise from north.
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, Copy, PartialEq, Eq)]
+pub enum Compass {
+    North, NorthNorthEast, NorthEast, EastNorthEast,
+    East, EastSouthEast, SouthEast, SouthSouthEast,
+    South, SouthSouthWest, SouthWest, WestSouthWest,
+    West, WestNorthWest, NorthWest, NorthNorthWest,
+}
+
+impl Compass {
+    /// All sixteen points, clockwise from north.
+    pub const ALL: [Compass; 16] = [
+        Self::North, Self::NorthNorthEast, Self::NorthEast, Self::EastNorthEast,
+        Self::East, Self::EastSouthEast, Self::SouthEast, Self::SouthSouthEast,
+        Self::South, Self::SouthSouthWest, Self::SouthWest, Self::WestSouthWest,
+        Self::West, Self::WestNorthWest, Self::NorthWest, Self::NorthNorthWest,
+    ];
+
+    /// The eight main points, clockwise from north.
+    pub const EIGHT: [Compass; 8] = [
+        Self::North, Self::NorthEast, Self::East, Self::SouthEast,
+        Self::South, Self::SouthWest, Self::West, Self::NorthWest,
+    ];
+
+    /// The four cardinal points, clockwise from north.
+    pub const CARDINAL: [Compass; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    /// Get index (0 = north, clockwise in 1/16 turns).
+    pub fn index(&self) -> u8 {
+        Self::ALL.iter().position(|c| c == self).unwrap() as u8
+    }
+
+    /// Get point from index (0 = north, clockwise in 1/16 turns); wraps around.
+    pub fn from_index(index: i32) -> Self {
+        Self::ALL[index.rem_euclid(16) as usize]
+    }
+
+    /// Get the bearing in degrees, clockwise from north.
+    pub fn degrees(&self) -> f64 {
+        self.index() as f64 * 22.5
+    }
+
+    /// Get the point nearest to the given bearing (degrees, clockwise from north).
+    pub fn from_degrees(degrees: f64) -> Self {
+        Self::from_index((degrees.rem_euclid(360.0) / 22.5).round() as i32)
+    }
+
+    /// Rotate clockwise by `steps` 1/16 turns (negative `steps` rotate counter-clockwise).
+    pub fn rotate(&self, steps: i32) -> Self {
+        Self::from_index(self.index() as i32 + steps)
+    }
+
+    /// Get the opposite direction.
+    pub fn opposite(&self) -> Self {
+        self.rotate(8)
+    }
+
+    /// Mirror east ↔ west, e.g. north-east → north-west.
+    pub fn mirror(&self) -> Self {
+        Self::from_index(-(self.index() as i32))
+    }
+
+    /// Is this one of the four cardinal points?
+    pub fn is_cardinal(&self) -> bool {
+        self.index().is_multiple_of(4)
+    }
+
+    /// Is this one of the eight main points?
+    pub fn is_eight_point(&self) -> bool {
+        self.index().is_multiple_of(2)
+    }
+
+    /// Get the nearest of the eight main points; in-betweens go clockwise,
+    /// e.g. north-northeast → north-east.
+    pub fn nearest_eight(&self) -> Self {
+        Self::from_index((self.index() as i32 + 1) / 2 * 2)
+    }
+
+    /// Abbreviation, e.g. `"NNE"`.
+    pub fn abbrev(&self) -> &'static str {
+        ["N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW", "NW", "NNW"][self.index() as usize]
+    }
+
+    /// Get the direction relative to the given `facing`, e.g. east is
+    /// [right][Relative::Right] when facing north.
+    ///
+    /// In-betweens go clockwise, like with [Compass::nearest_eight].
+    pub fn relative_to(&self, facing: Compass) -> Relative {
+        let diff = (self.index() as i32 - facing.index() as i32).rem_euclid(16);
+        Relative::from_index((diff + 1) / 2)
+    }
+
+    /// Generate a random direction (of the eight main points).
+    pub fn random() -> Self {
+        Self::random_with(&mut ChaosRng)
+    }
+
+    /// Generate a random direction (of the eight main points) using the given `rng`.
+    pub fn random_with<R: DiceRng + ?Sized>(rng: &mut R) -> Self {
+        let roll = rng.roll_die(8);
+        let dir = Self::EIGHT[roll as usize - 1];
+        if rng.is_recording() {
+            rng.record(RollRecord::new("Compass::random", "1d8", vec![roll], dir));
+        }
+        dir
+    }
+}
+
+impl Display for Compass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::North => "north",
+            Self::NorthNorthEast => "north-northeast",
+            Self::NorthEast => "northeast",
+            Self::EastNorthEast => "east-northeast",
+            Self::East => "east",
+            Self::EastSouthEast => "east-southeast",
+            Self::SouthEast => "southeast",
+            Self::SouthSouthEast => "south-southeast",
+            Self::South => "south",
+            Self::SouthSouthWest => "south-southwest",
+            Self::SouthWest => "southwest",
+            Self::WestSouthWest => "west-southwest",
+            Self::West => "west",
+            Self::WestNorthWest => "west-northwest",
+            Self::NorthWest => "northwest",
+            Self::NorthNorthWest => "north-northwest",
+        })
+    }
+}
+
+#[cfg(test)]
+mod compass_tests {
+    use super::*;
+
+    #[test]
+    fn rotation_and_opposites() {
+        assert_eq!(Compass::South, Compass::North.opposite());
+        assert_eq!(Compass::NorthEast, Compass::SouthWest.opposite());
+        assert_eq!(Compass::East, Compass::North.rotate(4));
+        assert_eq!(Compass::NorthNorthWest, Compass::North.rotate(-1));
+        assert_eq!(Compass::North, Compass::North.rotate(32));
+        for c in Compass::ALL {
+            assert_eq!(c, c.opposite().opposite());
+            assert_eq!(c, c.mirror().mirror());
+        }
+    }
+
+    #[test]
+    fn mirror_and_nearest() {
+        assert_eq!(Compass::NorthWest, Compass::NorthEast.mirror());
+        assert_eq!(Compass::North, Compass::North.mirror());
+        assert_eq!(Compass::South, Compass::South.mirror());
+        assert_eq!(Compass::NorthEast, Compass::NorthNorthEast.nearest_eight());
+        assert_eq!(Compass::North, Compass::NorthNorthWest.nearest_eight());
+        assert!(Compass::West.is_cardinal());
+        assert!(!Compass::SouthEast.is_cardinal());
+    }
+
+    #[test]
+    fn degrees() {
+        assert_eq!(90.0, Compass::East.degrees());
+        assert_eq!(Compass::EastNorthEast, Compass::from_degrees(70.0));
+        assert_eq!(Compass::North, Compass::from_degrees(355.0));
+        assert_eq!(Compass::NorthWest, Compass::from_degrees(-45.0));
+    }
+
+    #[test]
+    fn serde_and_display() {
+        assert_eq!("\"NorthEast\"", serde_json::to_string(&Compass::NorthEast).unwrap());
+        assert_eq!("west-southwest", Compass::WestSouthWest.to_string());
+        assert_eq!("WSW", Compass::WestSouthWest.abbrev());
+    }
+}
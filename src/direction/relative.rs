@@ -0,0 +1,165 @@
+//! Directions relative to one's facing - front, rear-left, etc.
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{audit::RollRecord, direction::{bilateral::Bilateral, compass::Compass}, rng::{ChaosRng, DiceRng}};
+
+/// Eight-way relative directions, clockwise from front.
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, Copy, PartialEq, Eq)]
+pub enum Relative {
+    Front, FrontRight, Right, RearRight,
+    Rear, RearLeft, Left, FrontLeft,
+}
+
+impl Relative {
+    /// All eight directions, clockwise from front.
+    pub const ALL: [Relative; 8] = [
+        Self::Front, Self::FrontRight, Self::Right, Self::RearRight,
+        Self::Rear, Self::RearLeft, Self::Left, Self::FrontLeft,
+    ];
+
+    /// Get index (0 = front, clockwise in 1/8 turns).
+    pub fn index(&self) -> u8 {
+        Self::ALL.iter().position(|r| r == self).unwrap() as u8
+    }
+
+    /// Get direction from index (0 = front, clockwise in 1/8 turns); wraps around.
+    pub fn from_index(index: i32) -> Self {
+        Self::ALL[index.rem_euclid(8) as usize]
+    }
+
+    /// Get the direction nearest to `degrees` clockwise from front.
+    pub fn from_degrees(degrees: f64) -> Self {
+        Self::from_index((degrees.rem_euclid(360.0) / 45.0).round() as i32)
+    }
+
+    /// Rotate clockwise by `steps` 1/8 turns (negative `steps` rotate counter-clockwise).
+    pub fn rotate(&self, steps: i32) -> Self {
+        Self::from_index(self.index() as i32 + steps)
+    }
+
+    /// Get the opposite direction.
+    pub fn opposite(&self) -> Self {
+        self.rotate(4)
+    }
+
+    /// Mirror left ↔ right, e.g. front-left → front-right.
+    pub fn mirror(&self) -> Self {
+        Self::from_index(-(self.index() as i32))
+    }
+
+    /// Get the absolute direction when facing `facing`, e.g. [right][Relative::Right]
+    /// is east when facing north.
+    pub fn to_absolute(&self, facing: Compass) -> Compass {
+        facing.rotate(self.index() as i32 * 2)
+    }
+
+    /// Get the left/right component, if any.
+    pub fn side(&self) -> Option<Bilateral> {
+        match self {
+            Self::FrontRight | Self::Right | Self::RearRight => Some(Bilateral::Right),
+            Self::FrontLeft | Self::Left | Self::RearLeft => Some(Bilateral::Left),
+            _ => None
+        }
+    }
+
+    /// Get the front/back component, if any.
+    pub fn end(&self) -> Option<Bilateral> {
+        match self {
+            Self::FrontLeft | Self::Front | Self::FrontRight => Some(Bilateral::Front),
+            Self::RearLeft | Self::Rear | Self::RearRight => Some(Bilateral::Back),
+            _ => None
+        }
+    }
+
+    /// Generate a random relative direction.
+    pub fn random() -> Self {
+        Self::random_with(&mut ChaosRng)
+    }
+
+    /// Generate a random relative direction using the given `rng`.
+    pub fn random_with<R: DiceRng + ?Sized>(rng: &mut R) -> Self {
+        let roll = rng.roll_die(8);
+        let dir = Self::ALL[roll as usize - 1];
+        if rng.is_recording() {
+            rng.record(RollRecord::new("Relative::random", "1d8", vec![roll], dir));
+        }
+        dir
+    }
+}
+
+impl From<Bilateral> for Relative {
+    fn from(value: Bilateral) -> Self {
+        match value {
+            Bilateral::Left => Self::Left,
+            Bilateral::Right => Self::Right,
+            Bilateral::Front => Self::Front,
+            Bilateral::Back => Self::Rear,
+        }
+    }
+}
+
+impl TryFrom<Relative> for Bilateral {
+    type Error = Relative;
+
+    /// Only the four "pure" directions convert; diagonals are handed back as the error.
+    fn try_from(value: Relative) -> Result<Self, Self::Error> {
+        match value {
+            Relative::Left => Ok(Self::Left),
+            Relative::Right => Ok(Self::Right),
+            Relative::Front => Ok(Self::Front),
+            Relative::Rear => Ok(Self::Back),
+            _ => Err(value)
+        }
+    }
+}
+
+impl Display for Relative {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Front => "front",
+            Self::FrontRight => "front-right",
+            Self::Right => "right",
+            Self::RearRight => "rear-right",
+            Self::Rear => "rear",
+            Self::RearLeft => "rear-left",
+            Self::Left => "left",
+            Self::FrontLeft => "front-left",
+        })
+    }
+}
+
+#[cfg(test)]
+mod relative_tests {
+    use super::*;
+
+    #[test]
+    fn absolute_round_trip() {
+        for facing in Compass::EIGHT {
+            for r in Relative::ALL {
+                assert_eq!(r, r.to_absolute(facing).relative_to(facing));
+            }
+        }
+        assert_eq!(Compass::East, Relative::Right.to_absolute(Compass::North));
+        assert_eq!(Compass::North, Relative::RearRight.to_absolute(Compass::SouthWest));
+        assert_eq!(Relative::Left, Compass::North.relative_to(Compass::East));
+    }
+
+    #[test]
+    fn opposite_and_mirror() {
+        assert_eq!(Relative::RearLeft, Relative::FrontRight.opposite());
+        assert_eq!(Relative::FrontLeft, Relative::FrontRight.mirror());
+        assert_eq!(Relative::Rear, Relative::Rear.mirror());
+    }
+
+    #[test]
+    fn bilateral_conversions() {
+        assert_eq!(Relative::Rear, Relative::from(Bilateral::Back));
+        assert_eq!(Ok(Bilateral::Left), Bilateral::try_from(Relative::Left));
+        assert!(Bilateral::try_from(Relative::FrontLeft).is_err());
+        assert_eq!(Some(Bilateral::Left), Relative::RearLeft.side());
+        assert_eq!(Some(Bilateral::Back), Relative::RearLeft.end());
+        assert_eq!(None, Relative::Front.side());
+    }
+}
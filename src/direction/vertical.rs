@@ -0,0 +1,29 @@
+//! Up and down.
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// Vertical directions.
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, Copy, PartialEq, Eq)]
+pub enum Vertical {
+    Up, Down,
+}
+
+impl Vertical {
+    /// Get the opposite direction.
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+        }
+    }
+}
+
+impl Display for Vertical {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Up => "up",
+            Self::Down => "down",
+        })
+    }
+}
@@ -0,0 +1,267 @@
+//! Timed effects - buffs, poisons, fatigue and whatnot.
+//!
+//! An [Effect] is a bunch of [Modifier]s with a [Duration]. Game time is kept
+//! in rounds by an [EffectTracker] - [advancing][EffectTracker::advance] it
+//! expires effects and tells what ended.
+//!
+//! ```
+//! use rpgassist::{effect::{Duration, Effect, EffectTracker}, modifier::stack::{Modifier, Source, Stacking, Target}, stat::{Stat, StatBase}};
+//!
+//! let mut fx = EffectTracker::new();
+//! fx.add(Effect::new("bull's strength", Duration::Minutes(1))
+//!     .with(Modifier::new(Source::Spell("bull's strength".into()), Target::Stat(StatBase::Str), 4, "enhancement", Stacking::HighestOfType)));
+//! assert_eq!(14, fx.apply(&Stat::Str { val: 10 }).value());
+//! assert!(fx.advance(9).is_empty());
+//! assert_eq!(1, fx.advance(1).len());
+//! assert_eq!(10, fx.apply(&Stat::Str { val: 10 }).value());
+//! ```
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ext::IsNamed, modifier::{HasModifier, stack::{Modifier, ModifierStack, Resolution, Source, Stacking, Target}}, stat::Stat};
+
+/// Rounds in a minute.
+pub const ROUNDS_PER_MINUTE: u64 = 10;
+/// Rounds in a day.
+pub const ROUNDS_PER_DAY: u64 = ROUNDS_PER_MINUTE * 60 * 24;
+
+/// How long an [Effect] lasts.
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Duration {
+    Rounds(u64),
+    Minutes(u64),
+    Days(u64),
+    /// Lasts until explicitly [removed][EffectTracker::remove].
+    #[default]
+    UntilRemoved,
+}
+
+impl Duration {
+    /// Get the duration in rounds, if it's timed at all. Absurdly long
+    /// durations top out at [u64::MAX] rounds.
+    pub fn rounds(&self) -> Option<u64> {
+        match self {
+            Self::Rounds(n) => Some(*n),
+            Self::Minutes(n) => Some(n.saturating_mul(ROUNDS_PER_MINUTE)),
+            Self::Days(n) => Some(n.saturating_mul(ROUNDS_PER_DAY)),
+            Self::UntilRemoved => None,
+        }
+    }
+}
+
+impl Display for Duration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rounds(n) => write!(f, "{n} round{}", if *n == 1 {""} else {"s"}),
+            Self::Minutes(n) => write!(f, "{n} minute{}", if *n == 1 {""} else {"s"}),
+            Self::Days(n) => write!(f, "{n} day{}", if *n == 1 {""} else {"s"}),
+            Self::UntilRemoved => write!(f, "until removed"),
+        }
+    }
+}
+
+/// A temporary effect.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Effect {
+    name: String,
+    pub duration: Duration,
+    /// Game time (in rounds) when the effect started.
+    #[serde(default)]
+    pub start: u64,
+    #[serde(default)]
+    pub modifiers: Vec<Modifier>,
+}
+
+impl Effect {
+    /// A new effect without any modifiers (yet).
+    pub fn new(name: &str, duration: Duration) -> Self {
+        Self { name: name.into(), duration, start: 0, modifiers: vec![] }
+    }
+
+    /// Add a modifier.
+    pub fn with(mut self, modifier: Modifier) -> Self {
+        self.modifiers.push(modifier);
+        self
+    }
+
+    /// Game time when the effect expires, if ever.
+    pub fn expires(&self) -> Option<u64> {
+        self.duration.rounds().map(|r| self.start.saturating_add(r))
+    }
+
+    /// Has the effect expired by `now`?
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires().is_some_and(|e| e <= now)
+    }
+
+    /// Rounds left at `now`, if timed.
+    pub fn remaining(&self, now: u64) -> Option<u64> {
+        self.expires().map(|e| e.saturating_sub(now))
+    }
+}
+
+impl IsNamed for Effect {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl HasModifier for Effect {
+    /// Net value of all the additive (and highest-of-type) modifiers - percentages
+    /// need a base value to apply to, see [EffectTracker::resolve] for those.
+    fn modifier(&self) -> i32 {
+        self.modifiers.iter()
+            .filter(|m| m.stacking != Stacking::Multiplicative)
+            .map(|m| m.value)
+            .sum()
+    }
+}
+
+impl Display for Effect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name, self.duration)
+    }
+}
+
+/// Keeps track of game time and the active [Effect]s.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct EffectTracker {
+    /// Current game time, in rounds.
+    now: u64,
+    effects: Vec<Effect>,
+}
+
+impl EffectTracker {
+    /// A new tracker at time zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the current game time (in rounds).
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Add an effect, starting now.
+    pub fn add(&mut self, mut effect: Effect) {
+        effect.start = self.now;
+        self.effects.push(effect)
+    }
+
+    /// Remove effect(s) by name. Returns what was removed.
+    pub fn remove(&mut self, name: &str) -> Vec<Effect> {
+        let (off, on) = std::mem::take(&mut self.effects).into_iter().partition(|e| e.name == name);
+        self.effects = on;
+        off
+    }
+
+    /// Advance game time by `rounds`. Returns the effects which ended.
+    pub fn advance(&mut self, rounds: u64) -> Vec<Effect> {
+        self.now = self.now.saturating_add(rounds);
+        let now = self.now;
+        let (ended, on) = std::mem::take(&mut self.effects).into_iter().partition(|e| e.is_expired(now));
+        self.effects = on;
+        ended
+    }
+
+    /// Iterate through the active effects.
+    pub fn iter(&self) -> impl Iterator<Item = &Effect> {
+        self.effects.iter()
+    }
+
+    /// Get the modifiers of all active effects as a [ModifierStack].
+    pub fn stack(&self) -> ModifierStack {
+        let mut stack = ModifierStack::new();
+        self.feed(&mut stack);
+        stack
+    }
+
+    /// Resolve `target` from `base` with the active effects.
+    pub fn resolve(&self, target: &Target, base: i32) -> Resolution {
+        self.stack().resolve(target, base)
+    }
+
+    /// Get `stat` as modified by the active effects.
+    pub fn apply(&self, stat: &Stat) -> Stat {
+        let base = stat.stat_base();
+        base.with(self.resolve(&Target::Stat(base), stat.value()).value)
+    }
+
+    /// Add the modifiers of all active effects to `stack`, e.g. alongside those from items.
+    pub fn feed(&self, stack: &mut ModifierStack) {
+        self.effects.iter().flat_map(|e| e.modifiers.iter()).for_each(|m| stack.add(m.clone()));
+    }
+
+    /// Remove all effects from the given `source`.
+    pub fn remove_source(&mut self, source: &Source) -> Vec<Effect> {
+        let (off, on) = std::mem::take(&mut self.effects).into_iter()
+            .partition(|e| e.modifiers.iter().any(|m| m.source == *source));
+        self.effects = on;
+        off
+    }
+}
+
+#[cfg(test)]
+mod effect_tests {
+    use crate::stat::StatBase;
+
+    use super::*;
+
+    fn poison() -> Effect {
+        Effect::new("spider venom", Duration::Rounds(3))
+            .with(Modifier::new(Source::Injury("spider venom".into()), Target::Stat(StatBase::Con), -2, "poison", Stacking::Additive))
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(Some(3), Duration::Rounds(3).rounds());
+        assert_eq!(Some(20), Duration::Minutes(2).rounds());
+        assert_eq!(Some(14400), Duration::Days(1).rounds());
+        assert_eq!(None, Duration::UntilRemoved.rounds());
+        assert_eq!("1 minute", Duration::Minutes(1).to_string());
+        assert_eq!(Some(u64::MAX), Duration::Days(u64::MAX / 2).rounds());
+        let mut fx = EffectTracker::new();
+        fx.advance(10);
+        fx.add(Effect::new("curse", Duration::Days(u64::MAX)));
+        assert_eq!(1, fx.advance(u64::MAX).len());
+        assert_eq!(u64::MAX, fx.now());
+    }
+
+    #[test]
+    fn expiry() {
+        let mut fx = EffectTracker::new();
+        fx.advance(5);
+        fx.add(poison());
+        fx.add(Effect::new("fatigue", Duration::UntilRemoved)
+            .with(Modifier::new(Source::Other("march".into()), Target::Stat(StatBase::Con), -1, "fatigue", Stacking::Additive)));
+        assert_eq!(Some(8), fx.iter().next().unwrap().expires());
+        assert_eq!(7, fx.apply(&Stat::Con { val: 10 }).value());
+        assert!(fx.advance(2).is_empty());
+        let ended = fx.advance(1);
+        assert_eq!(1, ended.len());
+        assert_eq!("spider venom", ended[0].name());
+        assert!(fx.advance(ROUNDS_PER_DAY * 100).is_empty());
+        assert_eq!(9, fx.apply(&Stat::Con { val: 10 }).value());
+        assert_eq!(1, fx.remove("fatigue").len());
+        assert_eq!(0, fx.iter().count());
+    }
+
+    #[test]
+    fn feeds_stack() {
+        let mut fx = EffectTracker::new();
+        fx.add(poison());
+        let mut stack = ModifierStack::new();
+        stack.add(Modifier::new(Source::Item("amulet".into()), Target::Stat(StatBase::Con), 1, "health", Stacking::Additive));
+        fx.feed(&mut stack);
+        assert_eq!(9, stack.resolve(&Target::Stat(StatBase::Con), 10).value);
+        assert_eq!(-2, fx.iter().next().unwrap().modifier());
+        let haste = Effect::new("haste", Duration::Rounds(5))
+            .with(Modifier::new(Source::Spell("haste".into()), Target::Check("attack".into()), 150, "haste", Stacking::Multiplicative))
+            .with(Modifier::new(Source::Spell("haste".into()), Target::Check("attack".into()), 1, "haste", Stacking::Additive));
+        assert_eq!(1, haste.modifier());
+        let json = serde_json::to_string(&fx).unwrap();
+        assert_eq!(fx, serde_json::from_str(&json).unwrap());
+    }
+}
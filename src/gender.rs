@@ -4,15 +4,19 @@
 //! **a)** 50/50 [Gender::random]
 //! **b)** [GenderBias]'ed [Gender::random_biased].
 //! 
+//! Both have `*_with(rng)` variants which roll on the given [DiceRng].
+//! 
+//! # Odds
+//! Exact [odds][Odds] behind the biases are available via [Gender::odds_biased].
+//! 
 //! # Resolvers
 //! **a)** direct resolver, [Gender::resolve]
 //! **b)** biased resolver [Gender::resolve_biased],
 //! which both work in-place with `&mut self`.
 //! 
-use dicebag::DiceExt;
 use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::resolve::resolve_in_place::ResolveInPlace;
+use crate::{analysis::{Distribution, Odds}, audit::RollRecord, modifier::HasModifier, resolve::resolve_in_place::ResolveInPlace, rng::{ChaosRng, DiceRng}};
 
 /// Genders, obviously …
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Copy)]
@@ -41,7 +45,7 @@ where D: Deserializer<'de> {
     Ok(v.min(10))
 }
 
-#[derive(Debug, Deserialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
 /// A bias value/modifier for dice rolls. What the set value actually
 /// means, depends on [Bias10]'s usage context itself.
 pub struct Bias10 {
@@ -49,13 +53,7 @@ pub struct Bias10 {
     value: u32,
 }
 
-impl Default for Bias10 {
-    fn default() -> Self {
-        Self { value: 0 }
-    }
-}
-
-#[derive(Debug, Deserialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
 pub enum GenderBias {
     /// Male bias. The higher the [Bias10], the more likely result will be [Gender::Male].
     Male(Bias10),
@@ -66,12 +64,20 @@ pub enum GenderBias {
     /// Approx ⅔ of rolls will result in female.
     Female23,
     /// No bias one way or the other. About 1:1 distribution between gender choices.
-    None
+    #[default]
+    None// RL distribution is "close enough" to 1:1.
 }
 
-impl Default for GenderBias {
-    fn default() -> Self {
-        Self::None// RL distribution is "close enough" to 1:1.
+impl HasModifier for GenderBias {
+    /// Get the modifier applied to the d20 roll in [Gender::random_biased].
+    fn modifier(&self) -> i32 {
+        match self {
+            Self::Male23 => -3,//-3.333 …
+            Self::Male(v) => -(v.value as i32),
+            Self::Female23 => 4,//+3.333 …
+            Self::Female(v) => v.value as i32,
+            Self::None => 0
+        }
     }
 }
 
@@ -87,26 +93,53 @@ impl Gender {
     /// It is ever so slightly female-biased, but the difference is too small to affect
     /// dice rolls.
     pub fn random() -> Self {
-        Self::random_biased(GenderBias::None)
+        Self::random_with(&mut ChaosRng)
+    }
+
+    /// Generate a random gender using the given `rng`.
+    pub fn random_with<R: DiceRng + ?Sized>(rng: &mut R) -> Self {
+        Self::random_biased_with(GenderBias::None, rng)
     }
 
     /// Generate a random gender, with or without bias toward one or the other.
     pub fn random_biased(bias: GenderBias) -> Self {
-        if 1.d20() +
-        match bias {
-            GenderBias::Male23 => -3,//-3.333 …
-            GenderBias::Male(v) => -(v.value as i32),
-            GenderBias::Female23 => 4,//+3.333 …
-            GenderBias::Female(v) => v.value as i32,
-            _ => 0
-        } <= 10 { Self::Male }
-        else { Self::Female }
+        Self::random_biased_with(bias, &mut ChaosRng)
+    }
+
+    /// Generate a random gender using the given `rng`, with or without bias
+    /// toward one or the other.
+    pub fn random_biased_with<R: DiceRng + ?Sized>(bias: GenderBias, rng: &mut R) -> Self {
+        let roll = rng.roll_die(20);
+        let gender = if roll as i32 + bias.modifier() <= 10 { Self::Male }
+        else { Self::Female };
+        if rng.is_recording() {
+            let dice = match bias.modifier() {
+                0 => "1d20".to_string(),
+                m => format!("1d20{m:+}")
+            };
+            rng.record(RollRecord::new("Gender::random_biased", dice, vec![roll], format!("{gender:?}")));
+        }
+        gender
+    }
+
+    /// Get the exact [Odds] of [Gender::random_biased] with given `bias`.
+    pub fn odds_biased(bias: GenderBias) -> Odds<Self> {
+        let d20 = Distribution::die(20).add(&Distribution::constant(bias.modifier()));
+        let mut odds = Odds::new();
+        odds.add(Self::Male, d20.at_most(10));
+        odds.add(Self::Female, d20.at_least(11));
+        odds
     }
 
     /// Get set [Gender] or a random one.
     pub fn get_or_random(&self) -> Self {
+        self.get_or_random_with(&mut ChaosRng)
+    }
+
+    /// Get set [Gender] or a random one using the given `rng`.
+    pub fn get_or_random_with<R: DiceRng + ?Sized>(&self, rng: &mut R) -> Self {
         match self {
-            Self::Unspecified => Self::random_biased(GenderBias::None),
+            Self::Unspecified => Self::random_with(rng),
             _ => *self
         }
     }
@@ -116,9 +149,14 @@ impl Gender {
     /// 
     /// If the [gender][Gender] has already been resolved, noting happens.
     pub fn resolve_biased(&mut self, bias: GenderBias) {
-        match self {
-            Self::Unspecified => *self = Self::random_biased(bias),
-            _ => ()
+        self.resolve_biased_with(bias, &mut ChaosRng)
+    }
+
+    /// Resolve an [unspecified][Gender::Unspecified] [gender][Gender] in-place
+    /// using the given `rng`. See [Gender::resolve_biased].
+    pub fn resolve_biased_with<R: DiceRng + ?Sized>(&mut self, bias: GenderBias, rng: &mut R) {
+        if *self == Self::Unspecified {
+            *self = Self::random_biased_with(bias, rng)
         }
     }
 }
@@ -177,9 +215,6 @@ impl Default for Gender {
 impl ResolveInPlace for Gender {
     /// Resolve gender (randomly) in-place with 50/50 "bias".
     fn resolve(&mut self) {
-        match self {
-            Self::Unspecified => *self = Self::random_biased(GenderBias::None),
-            _ => ()
-        }
+        self.resolve_biased(GenderBias::None)
     }
 }
\ No newline at end of file
@@ -0,0 +1,7 @@
+//! Tactical map grid coordinates - [square][square::Square] and [hex][hex::Hex] ones.
+//!
+//! Both tie in with [direction][crate::direction]s: step towards a [Compass][crate::direction::compass::Compass]
+//! point, or towards a [Relative][crate::direction::relative::Relative] (or
+//! [Bilateral][crate::direction::bilateral::Bilateral]) direction given a facing.
+pub mod hex;
+pub mod square;
@@ -0,0 +1,254 @@
+//! Hex grid coordinates - axial `(q, r)`, with cube `s = -q - r` derived.
+//!
+//! Hexes are flat-topped: the six neighbors lie north, north-east, south-east,
+//! south, south-west and north-west.
+use std::{fmt::Display, ops::{Add, Sub}};
+
+use serde::{Deserialize, Serialize};
+
+use crate::direction::{arc::AttackArc, compass::Compass, relative::Relative};
+
+/// Axial offsets of the six neighbors, clockwise from north.
+const DIRECTIONS: [Hex; 6] = [
+    Hex::new(0, -1), Hex::new(1, -1), Hex::new(1, 0),
+    Hex::new(0, 1), Hex::new(-1, 1), Hex::new(-1, 0),
+];
+
+/// The compass points with a neighboring hex, clockwise from north.
+pub const HEX_DIRECTIONS: [Compass; 6] = [
+    Compass::North, Compass::NorthEast, Compass::SouthEast,
+    Compass::South, Compass::SouthWest, Compass::NorthWest,
+];
+
+/// A hex on a hex grid.
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    /// A hex at axial (`q`, `r`).
+    pub const fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    /// A hex at cube (`q`, `r`, `s`).
+    ///
+    /// # Panic
+    /// If `q + r + s` isn't zero.
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Self {
+        if q + r + s != 0 {
+            panic!("Cube coordinates ({q}, {r}, {s}) don't add up to zero!")
+        }
+        Self::new(q, r)
+    }
+
+    /// The third, cube, coordinate.
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// Index (into [HEX_DIRECTIONS]) of the neighbor nearest to `dir`;
+    /// in-betweens (e.g. east) go clockwise.
+    fn direction_index(dir: Compass) -> usize {
+        ((dir.degrees() / 60.0).round() as usize) % 6
+    }
+
+    /// Get the neighbor towards `dir`; directions without a neighbor of their
+    /// own go clockwise, e.g. east → south-east.
+    pub fn step(&self, dir: Compass) -> Self {
+        *self + DIRECTIONS[Self::direction_index(dir)]
+    }
+
+    /// Get the neighbor towards `dir` relative to `facing`.
+    pub fn step_relative<D: Into<Relative>>(&self, facing: Compass, dir: D) -> Self {
+        self.step(dir.into().to_absolute(facing))
+    }
+
+    /// Get the six neighbors, clockwise from north.
+    pub fn neighbors(&self) -> Vec<Self> {
+        DIRECTIONS.iter().map(|d| *self + *d).collect()
+    }
+
+    /// Distance in steps.
+    pub fn distance(&self, other: &Hex) -> u32 {
+        let d = *self - *other;
+        (d.q.unsigned_abs() + d.r.unsigned_abs() + d.s().unsigned_abs()) / 2
+    }
+
+    /// Get the exact compass bearing (degrees clockwise from north) towards
+    /// `other`, if it isn't this very hex.
+    pub fn bearing_to(&self, other: &Hex) -> Option<f64> {
+        if self == other {
+            return None
+        }
+        let (q, r) = ((other.q - self.q) as f64, (other.r - self.r) as f64);
+        // flat-top axial → cartesian, y growing north
+        let (x, y) = (1.5 * q, -(3f64.sqrt()) * (r + q / 2.0));
+        Some(x.atan2(y).to_degrees().rem_euclid(360.0))
+    }
+
+    /// Get the compass direction (of [HEX_DIRECTIONS]) nearest to the bearing
+    /// towards `other`, if it isn't this very hex.
+    pub fn direction_to(&self, other: &Hex) -> Option<Compass> {
+        self.bearing_to(other).map(|b| HEX_DIRECTIONS[(b / 60.0).round() as usize % 6])
+    }
+
+    /// Round fractional cube coordinates to the nearest hex.
+    fn round(q: f64, r: f64, s: f64) -> Self {
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+        Self::new(rq as i32, rr as i32)
+    }
+
+    /// Get the [arc][AttackArc] an `attacker` is in, this one being the
+    /// defender facing `facing` - if the attacker isn't in the very same spot.
+    ///
+    /// The arc goes by the exact bearing, so e.g. a north-facing defender can
+    /// be flanked from hexes due east or west even without a neighbor there.
+    pub fn arc_of(&self, facing: Compass, attacker: &Hex) -> Option<AttackArc> {
+        self.bearing_to(attacker).map(|b| AttackArc::from_bearing(b, facing))
+    }
+
+    /// Draw a line to `other`, both ends included.
+    pub fn line_to(&self, other: &Hex) -> Vec<Self> {
+        let n = self.distance(other);
+        if n == 0 {
+            return vec![*self]
+        }
+        // nudge off the exact hex edges so ties round consistently…
+        let a = (self.q as f64 + 1e-6, self.r as f64 + 1e-6, self.s() as f64 - 2e-6);
+        let b = (other.q as f64 + 1e-6, other.r as f64 + 1e-6, other.s() as f64 - 2e-6);
+        (0..=n).map(|i| {
+            let t = i as f64 / n as f64;
+            Self::round(a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t, a.2 + (b.2 - a.2) * t)
+        }).collect()
+    }
+
+    /// Get the hexes exactly `radius` steps away, clockwise from the south-west corner.
+    pub fn ring(&self, radius: u32) -> Vec<Self> {
+        if radius == 0 {
+            return vec![*self]
+        }
+        let r = radius as i32;
+        let mut at = *self + Self::new(DIRECTIONS[4].q * r, DIRECTIONS[4].r * r);
+        let mut ring = vec![];
+        for dir in DIRECTIONS {
+            for _ in 0..radius {
+                ring.push(at);
+                at = at + dir;
+            }
+        }
+        ring
+    }
+
+    /// Get the hexes within `radius` steps, ring by ring outwards starting from this one.
+    pub fn spiral(&self, radius: u32) -> Vec<Self> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+
+    /// Get the hexes within `radius` visible from this one.
+    ///
+    /// A hex is visible if nothing on the line to it (ends excluded) is
+    /// `blocked` - so walls themselves are visible, what's behind them isn't.
+    pub fn field_of_view<F: Fn(&Hex) -> bool>(&self, radius: u32, blocked: F) -> Vec<Self> {
+        self.spiral(radius).into_iter()
+            .filter(|h| {
+                let line = self.line_to(h);
+                line.len() < 3 || !line[1..line.len() - 1].iter().any(&blocked)
+            })
+            .collect()
+    }
+}
+
+impl Add for Hex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Display for Hex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.q, self.r)
+    }
+}
+
+#[cfg(test)]
+mod hex_tests {
+    use crate::direction::bilateral::Bilateral;
+
+    use super::*;
+
+    const O: Hex = Hex::new(0, 0);
+
+    #[test]
+    fn neighbors_and_directions() {
+        assert!(O.neighbors().iter().all(|n| O.distance(n) == 1));
+        for (i, dir) in HEX_DIRECTIONS.iter().enumerate() {
+            assert_eq!(DIRECTIONS[i], O.step(*dir));
+            assert_eq!(Some(*dir), O.direction_to(&O.step(*dir)));
+        }
+        assert_eq!(O.step(Compass::SouthEast), O.step(Compass::East));
+        assert_eq!(O.step(Compass::North), O.step_relative(Compass::South, Bilateral::Back));
+        // ≈33.7°, nearer to north-east (60°) than north
+        assert_eq!(Some(Compass::NorthEast), O.direction_to(&Hex::new(5, -9)));
+    }
+
+    #[test]
+    fn north_facing_can_be_flanked() {
+        assert_eq!(Bilateral::Right, O.arc_of(Compass::North, &Hex::new(2, -1)).unwrap().arc);
+        assert_eq!(Bilateral::Left, O.arc_of(Compass::North, &Hex::new(-4, 2)).unwrap().arc);
+        assert_eq!(Bilateral::Front, O.arc_of(Compass::North, &Hex::new(1, -1)).unwrap().arc);
+        assert_eq!(Bilateral::Back, O.arc_of(Compass::South, &Hex::new(0, -3)).unwrap().arc);
+        assert!(O.spiral(8).iter().any(|h| O.arc_of(Compass::South, h).is_some_and(|a| a.is_flank())));
+    }
+
+    #[test]
+    fn distance_and_cube() {
+        let h = Hex::from_cube(3, -1, -2);
+        assert_eq!(-2, h.s());
+        assert_eq!(3, O.distance(&h));
+    }
+
+    #[test]
+    fn lines() {
+        let target = Hex::new(4, -2);
+        let line = O.line_to(&target);
+        assert_eq!(5, line.len());
+        assert_eq!(target, *line.last().unwrap());
+        assert!(line.windows(2).all(|w| w[0].distance(&w[1]) == 1));
+    }
+
+    #[test]
+    fn rings_and_spirals() {
+        assert_eq!(1, O.ring(0).len());
+        assert_eq!(12, O.ring(2).len());
+        assert!(O.ring(3).iter().all(|h| O.distance(h) == 3));
+        assert_eq!(19, O.spiral(2).len());
+    }
+
+    #[test]
+    fn walls_block_view() {
+        let wall = Hex::new(0, -1);
+        let fov = O.field_of_view(3, |h| *h == wall);
+        assert!(fov.contains(&wall));
+        assert!(!fov.contains(&Hex::new(0, -2)));
+        assert!(fov.contains(&Hex::new(0, 2)));
+    }
+}
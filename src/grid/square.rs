@@ -0,0 +1,211 @@
+//! Square grid coordinates.
+//!
+//! `x` grows east, `y` grows south (like on screen); north is `-y`.
+use std::{fmt::Display, ops::{Add, Sub}};
+
+use serde::{Deserialize, Serialize};
+
+use crate::direction::{arc::AttackArc, compass::Compass, relative::Relative};
+
+/// A square on a square grid.
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Square {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Square {
+    /// A square at (`x`, `y`).
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// Get the unit offset towards `dir`; sixteen-point in-betweens go
+    /// to the [nearest eight][Compass::nearest_eight].
+    pub fn offset(dir: Compass) -> Self {
+        match dir.nearest_eight() {
+            Compass::North => Self::new(0, -1),
+            Compass::NorthEast => Self::new(1, -1),
+            Compass::East => Self::new(1, 0),
+            Compass::SouthEast => Self::new(1, 1),
+            Compass::South => Self::new(0, 1),
+            Compass::SouthWest => Self::new(-1, 1),
+            Compass::West => Self::new(-1, 0),
+            _ => Self::new(-1, -1),
+        }
+    }
+
+    /// Get the neighbor towards `dir`.
+    pub fn step(&self, dir: Compass) -> Self {
+        *self + Self::offset(dir)
+    }
+
+    /// Get the neighbor towards `dir` relative to `facing`, e.g.
+    /// `step_relative(Compass::North, Bilateral::Left)` steps west.
+    pub fn step_relative<D: Into<Relative>>(&self, facing: Compass, dir: D) -> Self {
+        self.step(dir.into().to_absolute(facing))
+    }
+
+    /// Get the four orthogonal neighbors, clockwise from north.
+    pub fn neighbors4(&self) -> Vec<Self> {
+        Compass::CARDINAL.iter().map(|c| self.step(*c)).collect()
+    }
+
+    /// Get all eight neighbors, clockwise from north.
+    pub fn neighbors8(&self) -> Vec<Self> {
+        Compass::EIGHT.iter().map(|c| self.step(*c)).collect()
+    }
+
+    /// Distance in (eight-way, diagonals included) steps.
+    pub fn distance(&self, other: &Square) -> u32 {
+        (self.x - other.x).unsigned_abs().max((self.y - other.y).unsigned_abs())
+    }
+
+    /// Distance in (four-way) orthogonal steps.
+    pub fn manhattan(&self, other: &Square) -> u32 {
+        (self.x - other.x).unsigned_abs() + (self.y - other.y).unsigned_abs()
+    }
+
+    /// Get the (sixteen-point) compass direction towards `other`, if it isn't this very square.
+    pub fn direction_to(&self, other: &Square) -> Option<Compass> {
+        let (dx, dy) = (other.x - self.x, other.y - self.y);
+        if dx == 0 && dy == 0 {
+            return None
+        }
+        Some(Compass::from_degrees((dx as f64).atan2(-dy as f64).to_degrees()))
+    }
+
+    /// Get the [arc][AttackArc] an `attacker` is in, this one being the
+    /// defender facing `facing` - if the attacker isn't in the very same spot.
+    pub fn arc_of(&self, facing: Compass, attacker: &Square) -> Option<AttackArc> {
+        self.direction_to(attacker).map(|from| AttackArc::from_direction(from, facing))
+    }
+
+    /// Draw a line to `other` (Bresenham), both ends included.
+    pub fn line_to(&self, other: &Square) -> Vec<Self> {
+        let (dx, dy) = ((other.x - self.x).abs(), -(other.y - self.y).abs());
+        let (sx, sy) = ((other.x - self.x).signum(), (other.y - self.y).signum());
+        let mut err = dx + dy;
+        let mut at = *self;
+        let mut line = vec![at];
+        while at != *other {
+            let e2 = 2 * err;
+            if e2 >= dy { err += dy; at.x += sx; }
+            if e2 <= dx { err += dx; at.y += sy; }
+            line.push(at);
+        }
+        line
+    }
+
+    /// Get the squares exactly `radius` steps away, clockwise from the north-west corner.
+    pub fn ring(&self, radius: u32) -> Vec<Self> {
+        if radius == 0 {
+            return vec![*self]
+        }
+        let r = radius as i32;
+        let mut at = Self::new(self.x - r, self.y - r);
+        let mut ring = vec![];
+        for dir in [Compass::East, Compass::South, Compass::West, Compass::North] {
+            for _ in 0..2 * r {
+                ring.push(at);
+                at = at.step(dir);
+            }
+        }
+        ring
+    }
+
+    /// Get the squares within `radius` steps, ring by ring outwards starting from this one.
+    pub fn spiral(&self, radius: u32) -> Vec<Self> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+
+    /// Get the squares within `radius` visible from this one.
+    ///
+    /// A square is visible if nothing on the line to it (ends excluded) is
+    /// `blocked` - so walls themselves are visible, what's behind them isn't.
+    pub fn field_of_view<F: Fn(&Square) -> bool>(&self, radius: u32, blocked: F) -> Vec<Self> {
+        self.spiral(radius).into_iter()
+            .filter(|s| {
+                let line = self.line_to(s);
+                line.len() < 3 || !line[1..line.len() - 1].iter().any(&blocked)
+            })
+            .collect()
+    }
+}
+
+impl Add for Square {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Square {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Display for Square {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod square_tests {
+    use crate::direction::bilateral::Bilateral;
+
+    use super::*;
+
+    const O: Square = Square::new(0, 0);
+
+    #[test]
+    fn steps_and_facing() {
+        assert_eq!(Square::new(0, -1), O.step(Compass::North));
+        assert_eq!(Square::new(1, -1), O.step(Compass::NorthNorthEast));
+        assert_eq!(Square::new(-1, 0), O.step_relative(Compass::North, Bilateral::Left));
+        assert_eq!(Square::new(0, 1), O.step_relative(Compass::East, Relative::Right));
+        assert_eq!(Some(Compass::East), O.direction_to(&Square::new(5, 0)));
+        assert_eq!(Some(Compass::SouthWest), O.direction_to(&Square::new(-2, 2)));
+        assert_eq!(None, O.direction_to(&O));
+    }
+
+    #[test]
+    fn distances() {
+        let p = Square::new(3, -4);
+        assert_eq!(4, O.distance(&p));
+        assert_eq!(7, O.manhattan(&p));
+        assert!(O.neighbors8().iter().all(|n| O.distance(n) == 1));
+        assert!(O.neighbors4().iter().all(|n| O.manhattan(n) == 1));
+    }
+
+    #[test]
+    fn lines() {
+        let line = O.line_to(&Square::new(4, 2));
+        assert_eq!(5, line.len());
+        assert_eq!(O, line[0]);
+        assert_eq!(Square::new(4, 2), line[4]);
+        assert!(line.windows(2).all(|w| w[0].distance(&w[1]) == 1));
+    }
+
+    #[test]
+    fn rings_and_spirals() {
+        assert_eq!(1, O.ring(0).len());
+        assert_eq!(16, O.ring(2).len());
+        assert!(O.ring(3).iter().all(|s| O.distance(s) == 3));
+        assert_eq!(25, O.spiral(2).len());
+    }
+
+    #[test]
+    fn walls_block_view() {
+        let wall = |s: &Square| s.x == 1;
+        let fov = O.field_of_view(3, wall);
+        assert!(fov.contains(&Square::new(1, 0)));
+        assert!(!fov.contains(&Square::new(2, 0)));
+        assert!(fov.contains(&Square::new(-3, 0)));
+    }
+}
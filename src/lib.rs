@@ -1,13 +1,23 @@
+pub mod analysis;
+pub mod audit;
 pub mod body;
+pub mod check;
 pub mod details;
+pub mod dice;
 pub mod direction;
+pub mod effect;
 pub mod ext;
 pub use ext::NaturalJoin;
 pub mod id;
 pub mod gender;
+pub mod grid;
 pub mod misc;
 pub mod modifier;
 pub mod ranking;
 pub mod resolve;
+pub mod rng;
+pub mod scoped;
+pub mod skill;
 pub mod stat;
+pub mod table;
 pub mod serialize;
\ No newline at end of file
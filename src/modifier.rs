@@ -1,3 +1,5 @@
+pub mod stack;
+
 pub trait HasModifier {
     fn modifier(&self) -> i32;
 }
\ No newline at end of file
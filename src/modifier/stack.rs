@@ -0,0 +1,308 @@
+//! Modifier stack - typed [Modifier]s with sources, targets and stacking rules.
+//!
+//! Resolving a [target][Target] against a base value goes like this:
+//! 1. [additive][Stacking::Additive] modifiers all apply,
+//! 2. [highest-of-type][Stacking::HighestOfType] modifiers of the same kind don't
+//!    stack - only the biggest bonus and the worst penalty of each kind apply,
+//! 3. [multiplicative][Stacking::Multiplicative] modifiers (percentages) get
+//!    applied on top of the sum, one after another.
+//!
+//! ```
+//! use rpgassist::{modifier::stack::{Modifier, ModifierStack, Source, Stacking, Target}, stat::StatBase};
+//!
+//! let mut stack = ModifierStack::new();
+//! let str = Target::Stat(StatBase::Str);
+//! stack.add(Modifier::new(Source::Item("belt of giants".into()), str.clone(), 4, "enhancement", Stacking::HighestOfType));
+//! stack.add(Modifier::new(Source::Spell("bull's strength".into()), str.clone(), 2, "enhancement", Stacking::HighestOfType));
+//! stack.add(Modifier::new(Source::Injury("broken arm".into()), str.clone(), -1, "injury", Stacking::Additive));
+//! let r = stack.resolve(&str, 10);
+//! assert_eq!(13, r.value);
+//! assert_eq!(1, r.suppressed.len());
+//! ```
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{details::DetailedDisplay, modifier::HasModifier, stat::StatBase};
+
+/// Where a modifier comes from.
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Item(String),
+    Spell(String),
+    Injury(String),
+    Other(String),
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Item(s) |
+            Self::Spell(s) |
+            Self::Injury(s) |
+            Self::Other(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+/// What a modifier applies to.
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Target {
+    Stat(StatBase),
+    /// A skill, by name.
+    Skill(String),
+    /// A check, by name, e.g. `"initiative"`.
+    Check(String),
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Stat(s) => write!(f, "{s}"),
+            Self::Skill(s) |
+            Self::Check(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+/// How modifiers stack with each other.
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Stacking {
+    /// Always applies.
+    #[default]
+    Additive,
+    /// Only the biggest bonus (and worst penalty) of the same kind applies.
+    HighestOfType,
+    /// Value is a percentage, applied on top of the sum, e.g. `150` for ×1.5.
+    Multiplicative,
+}
+
+/// A single modifier.
+#[derive(Debug, Deserialize, Serialize, Hash, Clone, PartialEq, Eq)]
+pub struct Modifier {
+    pub source: Source,
+    pub target: Target,
+    pub value: i32,
+    /// Modifier type, e.g. `"enhancement"`, `"morale"`.
+    pub kind: String,
+    #[serde(default)]
+    pub stacking: Stacking,
+}
+
+impl Modifier {
+    /// Make a new modifier.
+    pub fn new(source: Source, target: Target, value: i32, kind: &str, stacking: Stacking) -> Self {
+        Self { source, target, value, kind: kind.into(), stacking }
+    }
+}
+
+impl HasModifier for Modifier {
+    /// The flat value - or 0 for [multiplicative][Stacking::Multiplicative] ones, whose
+    /// percentage needs a base value to apply to, see [ModifierStack::resolve].
+    fn modifier(&self) -> i32 {
+        match self.stacking {
+            Stacking::Multiplicative => 0,
+            _ => self.value
+        }
+    }
+}
+
+impl Display for Modifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.stacking {
+            Stacking::Multiplicative => write!(f, "×{}% {} ({})", self.value, self.kind, self.source),
+            _ => write!(f, "{:+} {} ({})", self.value, self.kind, self.source),
+        }
+    }
+}
+
+/// Result of [resolving][ModifierStack::resolve] modifiers.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Resolution {
+    pub target: Target,
+    pub base: i32,
+    /// The effective value.
+    pub value: i32,
+    /// Modifiers which applied.
+    pub applied: Vec<Modifier>,
+    /// Modifiers overridden by others of the same kind.
+    pub suppressed: Vec<Modifier>,
+}
+
+impl HasModifier for Resolution {
+    /// Net modifier, i.e. effective value - base.
+    fn modifier(&self) -> i32 {
+        self.value.saturating_sub(self.base)
+    }
+}
+
+impl Display for Resolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} ({:+})", self.target, self.value, self.modifier())
+    }
+}
+
+impl DetailedDisplay for Resolution {
+    fn detailed_display(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}: base {}", self.target, self.base)?;
+        for m in &self.applied {
+            writeln!(f, "  {m}")?;
+        }
+        for m in &self.suppressed {
+            writeln!(f, "  ({m}, suppressed)")?;
+        }
+        write!(f, "  = {}", self.value)
+    }
+}
+
+/// A bunch of [Modifier]s, e.g. of a single character.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct ModifierStack {
+    mods: Vec<Modifier>,
+}
+
+impl ModifierStack {
+    /// An empty stack.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a modifier.
+    pub fn add(&mut self, modifier: Modifier) {
+        self.mods.push(modifier)
+    }
+
+    /// Remove all modifiers from the given `source`. Returns what was removed.
+    pub fn remove_source(&mut self, source: &Source) -> Vec<Modifier> {
+        let (off, on) = std::mem::take(&mut self.mods).into_iter().partition(|m| m.source == *source);
+        self.mods = on;
+        off
+    }
+
+    /// Iterate through all the modifiers.
+    pub fn iter(&self) -> impl Iterator<Item = &Modifier> {
+        self.mods.iter()
+    }
+
+    /// Iterate through modifiers for the given `target`.
+    pub fn for_target<'a>(&'a self, target: &'a Target) -> impl Iterator<Item = &'a Modifier> {
+        self.mods.iter().filter(move |m| m.target == *target)
+    }
+
+    /// Resolve the effective value of `target` from `base`.
+    pub fn resolve(&self, target: &Target, base: i32) -> Resolution {
+        let mut applied = vec![];
+        let mut suppressed = vec![];
+        let mods: Vec<&Modifier> = self.for_target(target).collect();
+        for (i, m) in mods.iter().enumerate() {
+            let wins = match m.stacking {
+                Stacking::HighestOfType => !mods.iter().enumerate().any(|(j, o)|
+                    j != i && o.stacking == Stacking::HighestOfType && o.kind == m.kind
+                    // same direction (bonus vs. penalty), bigger - or equal but earlier
+                    && (o.value >= 0) == (m.value >= 0)
+                    && (o.value.unsigned_abs() > m.value.unsigned_abs() || (o.value.unsigned_abs() == m.value.unsigned_abs() && j < i))),
+                _ => true
+            };
+            if wins { applied.push((*m).clone()) } else { suppressed.push((*m).clone()) }
+        }
+        // in i64 (kept within i32 at every step) so huge values saturate instead of overflowing
+        let clamp = |v: i64| v.clamp(i32::MIN as i64, i32::MAX as i64);
+        let sum = applied.iter()
+            .filter(|m| m.stacking != Stacking::Multiplicative)
+            .fold(base as i64, |v, m| clamp(v + m.value as i64));
+        let value = applied.iter()
+            .filter(|m| m.stacking == Stacking::Multiplicative)
+            .fold(sum, |v, m| clamp(v * m.value as i64 / 100)) as i32;
+        Resolution { target: target.clone(), base, value, applied, suppressed }
+    }
+}
+
+#[cfg(test)]
+mod stack_tests {
+    use super::*;
+
+    fn m(value: i32, kind: &str, stacking: Stacking) -> Modifier {
+        Modifier::new(Source::Other(format!("{kind} {value}")), Target::Check("attack".into()), value, kind, stacking)
+    }
+
+    #[test]
+    fn additive_stacks() {
+        let mut s = ModifierStack::new();
+        s.add(m(2, "morale", Stacking::Additive));
+        s.add(m(3, "morale", Stacking::Additive));
+        let r = s.resolve(&Target::Check("attack".into()), 5);
+        assert_eq!(10, r.value);
+        assert_eq!(5, r.modifier());
+        assert!(r.suppressed.is_empty());
+    }
+
+    #[test]
+    fn highest_of_type() {
+        let mut s = ModifierStack::new();
+        s.add(m(2, "luck", Stacking::HighestOfType));
+        s.add(m(4, "luck", Stacking::HighestOfType));
+        s.add(m(4, "luck", Stacking::HighestOfType));
+        s.add(m(-1, "luck", Stacking::HighestOfType));
+        s.add(m(-3, "luck", Stacking::HighestOfType));
+        s.add(m(1, "insight", Stacking::HighestOfType));
+        let r = s.resolve(&Target::Check("attack".into()), 0);
+        // +4 luck, -3 luck, +1 insight
+        assert_eq!(2, r.value);
+        assert_eq!(3, r.applied.len());
+        assert_eq!(3, r.suppressed.len());
+    }
+
+    #[test]
+    fn multiplicative_after_sum() {
+        let mut s = ModifierStack::new();
+        s.add(m(150, "haste", Stacking::Multiplicative));
+        s.add(m(2, "morale", Stacking::Additive));
+        assert_eq!(15, s.resolve(&Target::Check("attack".into()), 8).value);
+        assert_eq!(0, m(150, "haste", Stacking::Multiplicative).modifier());
+        assert_eq!(2, m(2, "morale", Stacking::Additive).modifier());
+    }
+
+    #[test]
+    fn huge_values_saturate() {
+        let mut s = ModifierStack::new();
+        s.add(m(200, "rage", Stacking::Multiplicative));
+        assert_eq!(40_000_000, s.resolve(&Target::Check("attack".into()), 20_000_000).value);
+        assert_eq!(i32::MAX, s.resolve(&Target::Check("attack".into()), 2_000_000_000).value);
+        s.add(m(i32::MAX, "luck", Stacking::Additive));
+        s.add(m(i32::MAX, "morale", Stacking::Additive));
+        let r = s.resolve(&Target::Check("attack".into()), i32::MIN);
+        assert_eq!(i32::MAX, r.value);
+        assert_eq!(i32::MAX, r.modifier());
+        s.add(m(i32::MIN, "doom", Stacking::HighestOfType));
+        s.add(m(i32::MIN, "doom", Stacking::HighestOfType));
+        assert_eq!(1, s.resolve(&Target::Check("attack".into()), 0).suppressed.len());
+    }
+
+    #[test]
+    fn targets_and_sources() {
+        let mut s = ModifierStack::new();
+        let dex = Target::Stat(StatBase::Dex);
+        s.add(Modifier::new(Source::Injury("sprained wrist".into()), dex.clone(), -2, "injury", Stacking::Additive));
+        s.add(m(3, "morale", Stacking::Additive));
+        assert_eq!(8, s.resolve(&dex, 10).value);
+        assert_eq!(1, s.remove_source(&Source::Injury("sprained wrist".into())).len());
+        assert_eq!(10, s.resolve(&dex, 10).value);
+        let json = serde_json::to_string(&s).unwrap();
+        assert_eq!(s, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn breakdown() {
+        let mut s = ModifierStack::new();
+        s.add(m(2, "luck", Stacking::HighestOfType));
+        s.add(m(1, "luck", Stacking::HighestOfType));
+        let r = s.resolve(&Target::Check("attack".into()), 10);
+        assert_eq!("attack 12 (+2)", r.to_string());
+        let details = r.detailed().to_string();
+        assert!(details.contains("+2 luck (luck 2)"));
+        assert!(details.contains("(+1 luck (luck 1), suppressed)"));
+    }
+}
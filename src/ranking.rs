@@ -1,2 +1,4 @@
+pub mod advancement;
+pub mod descriptor;
 pub mod rank;
 pub use rank::Rank;
\ No newline at end of file
@@ -0,0 +1,401 @@
+//! Advancement - XP costs, training time, prerequisites and caps for raising a [Rank].
+//!
+//! ```
+//! use rpgassist::{ranking::{Rank, advancement::{Advancement, CostCurve, Ledger}, rank::IsRanked}, skill::{Skill, SkillList}, stat::{StatBase, StatBlock}};
+//!
+//! // capped at INT (the governing stat) modifier + 6
+//! let rules = Advancement::new(CostCurve::Quadratic(10)).with_cap(6);
+//! let lore = Skill::new("lore", StatBase::Int, "knowledge");
+//! let mut skills = SkillList::new();
+//! skills.learn(&lore);
+//! let mut ledger = Ledger::new(100);
+//! ledger.raise_skill(&rules, &mut skills, "lore", &StatBlock::new()).unwrap();
+//! ledger.raise_skill(&rules, &mut skills, "lore", &StatBlock::new()).unwrap();
+//! assert_eq!(Rank::from(2), skills.get("lore").unwrap().rank());
+//! // 10 + 40 spent
+//! assert_eq!(50, ledger.available());
+//! ```
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{modifier::HasModifier, ranking::{rank::IsRanked, Rank}, skill::SkillList, stat::{StatBase, StatBlock}};
+
+/// Cost (in XP or days) of reaching a rank.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CostCurve {
+    /// `n × rank`.
+    Linear(u32),
+    /// `n × rank²`.
+    Quadratic(u32),
+    /// Per-rank costs, starting from rank 1. Ranks past the end can't be bought.
+    Table(Vec<u32>),
+}
+
+impl CostCurve {
+    /// Get the cost of reaching `rank` from the one below it.
+    ///
+    /// Ranks of zero or below cost the same as rank 1.
+    ///
+    /// # Returns
+    /// **a)** the cost, or
+    /// **b)** `None` if the rank can't be bought (or costs more than a `u32` holds).
+    pub fn cost(&self, rank: Rank) -> Option<u32> {
+        let r = rank.value().max(1) as u32;
+        match self {
+            Self::Linear(n) => n.checked_mul(r),
+            Self::Quadratic(n) => n.checked_mul(r)?.checked_mul(r),
+            Self::Table(costs) => costs.get(r as usize - 1).copied(),
+        }
+    }
+
+    /// Get the total cost of going `from` → `to`, if it can be bought (and fits a `u32`).
+    pub fn cost_between(&self, from: Rank, to: Rank) -> Option<u32> {
+        (from.value()..to.value()).try_fold(0u32, |total, r| total.checked_add(self.cost(Rank::from(r + 1))?))
+    }
+}
+
+/// Something required before a rank can be raised.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Prerequisite {
+    /// A stat of at least the given value.
+    Stat { stat: StatBase, min: i32 },
+    /// Another skill of at least the given rank.
+    Skill { name: String, rank: i32 },
+}
+
+impl Prerequisite {
+    /// Is the prerequisite met?
+    pub fn is_met(&self, stats: &StatBlock, skills: &SkillList) -> bool {
+        match self {
+            Self::Stat { stat, min } => stats.value(*stat) >= *min,
+            Self::Skill { name, rank } => skills.get(name).is_some_and(|s| s.rank() >= *rank),
+        }
+    }
+}
+
+impl Display for Prerequisite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Stat { stat, min } => write!(f, "{stat} {min}"),
+            Self::Skill { name, rank } => write!(f, "{name} rank {rank}"),
+        }
+    }
+}
+
+/// Rank cap tied to a stat - stat [modifier][HasModifier] + `offset`.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct Cap {
+    /// The capping stat - the governing stat of whatever is raised, if `None`.
+    #[serde(default)]
+    pub stat: Option<StatBase>,
+    pub offset: i32,
+}
+
+impl Cap {
+    /// Get the max rank allowed by `stats`, with `governing` as the capping stat unless set otherwise.
+    ///
+    /// # Returns
+    /// **a)** the max rank, or
+    /// **b)** `None` if there's no stat to cap by.
+    pub fn max(&self, stats: &StatBlock, governing: Option<StatBase>) -> Option<Rank> {
+        let stat = self.stat.or(governing)?;
+        Some(Rank::from(stats.get(stat).modifier().saturating_add(self.offset)))
+    }
+}
+
+/// Failure to raise a [Rank].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdvanceError {
+    /// No cost defined for the rank, i.e. it can't be bought - or the rank is
+    /// at `i32::MAX` already and can't go any higher.
+    NoCost(i32),
+    /// The rank is capped.
+    Capped { rank: i32, cap: i32 },
+    /// A prerequisite isn't met.
+    Prerequisite(Prerequisite),
+    /// Not enough XP.
+    NotEnoughXp { needed: u32, available: u32 },
+    /// No such skill (in the skill list).
+    UnknownSkill(String),
+}
+
+impl Display for AdvanceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoCost(rank) => write!(f, "Rank {rank} cannot be bought"),
+            Self::Capped { rank, cap } => write!(f, "Rank {rank} is over the cap of {cap}"),
+            Self::Prerequisite(p) => write!(f, "Prerequisite not met: {p}"),
+            Self::NotEnoughXp { needed, available } => write!(f, "Not enough XP: {needed} needed, {available} available"),
+            Self::UnknownSkill(name) => write!(f, "No such skill: '{name}'"),
+        }
+    }
+}
+
+impl std::error::Error for AdvanceError {}
+
+/// Advancement rules for a rank - a skill, stat or whatnot.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Advancement {
+    /// XP cost per rank.
+    pub cost: CostCurve,
+    /// Training time (in days) per rank, if any.
+    #[serde(default)]
+    pub training: Option<CostCurve>,
+    #[serde(default)]
+    pub prerequisites: Vec<Prerequisite>,
+    #[serde(default)]
+    pub cap: Option<Cap>,
+}
+
+impl Advancement {
+    /// Rules with the given XP `cost` curve, no training time, prerequisites nor cap.
+    pub fn new(cost: CostCurve) -> Self {
+        Self { cost, training: None, prerequisites: vec![], cap: None }
+    }
+
+    /// Set the training time curve (in days).
+    pub fn with_training(mut self, days: CostCurve) -> Self {
+        self.training = Some(days);
+        self
+    }
+
+    /// Add a prerequisite.
+    pub fn with_prerequisite(mut self, prereq: Prerequisite) -> Self {
+        self.prerequisites.push(prereq);
+        self
+    }
+
+    /// Cap ranks at governing stat modifier + `offset`.
+    pub fn with_cap(mut self, offset: i32) -> Self {
+        self.cap = Some(Cap { stat: None, offset });
+        self
+    }
+
+    /// Cap ranks at `stat` modifier + `offset`, whatever the governing stat.
+    pub fn with_cap_on(mut self, stat: StatBase, offset: i32) -> Self {
+        self.cap = Some(Cap { stat: Some(stat), offset });
+        self
+    }
+
+    /// Check whether `rank` could be raised by one, and what it'd cost.
+    ///
+    /// A [cap][Cap] without a stat of its own goes by the `governing` stat - and
+    /// doesn't apply if there's none.
+    ///
+    /// Returns XP cost and training days.
+    pub fn check(&self, rank: Rank, governing: Option<StatBase>, stats: &StatBlock, skills: &SkillList) -> Result<(u32, u32), AdvanceError> {
+        let next = Rank::from(rank.value().checked_add(1).ok_or(AdvanceError::NoCost(rank.value()))?);
+        if let Some(p) = self.prerequisites.iter().find(|p| !p.is_met(stats, skills)) {
+            return Err(AdvanceError::Prerequisite(p.clone()))
+        }
+        if let Some(cap) = self.cap.and_then(|c| c.max(stats, governing)) && next > cap {
+            return Err(AdvanceError::Capped { rank: next.value(), cap: cap.value() })
+        }
+        let cost = self.cost.cost(next).ok_or(AdvanceError::NoCost(next.value()))?;
+        let days = match &self.training {
+            Some(t) => t.cost(next).ok_or(AdvanceError::NoCost(next.value()))?,
+            None => 0
+        };
+        Ok((cost, days))
+    }
+}
+
+/// A single rank purchase.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Purchase {
+    /// What was raised, e.g. skill name.
+    pub what: String,
+    pub from: i32,
+    pub to: i32,
+    pub xp: u32,
+    pub training_days: u32,
+}
+
+impl Display for Purchase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} → {}: {} XP", self.what, self.from, self.to, self.xp)?;
+        if self.training_days > 0 {
+            write!(f, ", {} day{} of training", self.training_days, if self.training_days == 1 {""} else {"s"})?;
+        }
+        Ok(())
+    }
+}
+
+/// XP pool and a ledger of what it was spent on.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct Ledger {
+    available: u32,
+    purchases: Vec<Purchase>,
+}
+
+impl Ledger {
+    /// A ledger with `xp` to spend.
+    pub fn new(xp: u32) -> Self {
+        Self { available: xp, purchases: vec![] }
+    }
+
+    /// Get the XP still available.
+    pub fn available(&self) -> u32 {
+        self.available
+    }
+
+    /// Get the total XP spent so far.
+    pub fn spent(&self) -> u32 {
+        self.purchases.iter().fold(0u32, |total, p| total.saturating_add(p.xp))
+    }
+
+    /// Get the purchases so far.
+    pub fn purchases(&self) -> &[Purchase] {
+        &self.purchases
+    }
+
+    /// Gain some XP.
+    pub fn grant(&mut self, xp: u32) {
+        self.available = self.available.saturating_add(xp)
+    }
+
+    /// Raise `ranked` by one as per `rules`, spending XP.
+    ///
+    /// Any skill prerequisites are looked up from `skills`. There's no governing
+    /// stat here, so only a cap on a [given stat][Advancement::with_cap_on] applies.
+    pub fn raise<R: IsRanked + ?Sized>(&mut self, rules: &Advancement, what: &str, ranked: &mut R, stats: &StatBlock, skills: &SkillList) -> Result<&Purchase, AdvanceError> {
+        let (xp, training_days) = rules.check(ranked.rank(), None, stats, skills)?;
+        self.commit(what, ranked.rank_mut(), xp, training_days)
+    }
+
+    /// Raise skill `name` of `skills` by one as per `rules`, spending XP.
+    ///
+    /// Caps go by the skill's governing stat, unless [given one][Advancement::with_cap_on].
+    pub fn raise_skill(&mut self, rules: &Advancement, skills: &mut SkillList, name: &str, stats: &StatBlock) -> Result<&Purchase, AdvanceError> {
+        let skill = skills.get(name).ok_or_else(|| AdvanceError::UnknownSkill(name.into()))?;
+        let (rank, governing) = (skill.rank(), skill.skill.governing);
+        let (xp, training_days) = rules.check(rank, Some(governing), stats, skills)?;
+        let skill = skills.get_mut(name).expect("skill was there a moment ago");
+        self.commit(name, skill.rank_mut(), xp, training_days)
+    }
+
+    /// Pay for and record a purchase, if there's XP enough.
+    fn commit(&mut self, what: &str, rank: &mut Rank, xp: u32, training_days: u32) -> Result<&Purchase, AdvanceError> {
+        if xp > self.available {
+            return Err(AdvanceError::NotEnoughXp { needed: xp, available: self.available })
+        }
+        let from = rank.value();
+        let to = from.checked_add(1).ok_or(AdvanceError::NoCost(from))?;
+        *rank = Rank::from(to);
+        self.available -= xp;
+        self.purchases.push(Purchase { what: what.into(), from, to, xp, training_days });
+        Ok(self.purchases.last().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod advancement_tests {
+    use crate::{skill::Skill, stat::Stat};
+
+    use super::*;
+
+    #[test]
+    fn curves() {
+        assert_eq!(Some(15), CostCurve::Linear(5).cost(Rank::from(3)));
+        assert_eq!(Some(45), CostCurve::Quadratic(5).cost(Rank::from(3)));
+        assert_eq!(Some(5), CostCurve::Quadratic(5).cost(Rank::from(-1)));
+        let table = CostCurve::Table(vec![1, 2, 4]);
+        assert_eq!(Some(4), table.cost(Rank::from(3)));
+        assert_eq!(None, table.cost(Rank::from(4)));
+        assert_eq!(Some(6), table.cost_between(Rank::from(1), Rank::from(3)));
+        assert_eq!(None, table.cost_between(Rank::from(0), Rank::from(4)));
+        assert_eq!(Some(0), table.cost_between(Rank::from(2), Rank::from(2)));
+        assert_eq!(None, CostCurve::Quadratic(5000).cost(Rank::from(1000)));
+        assert_eq!(None, CostCurve::Linear(u32::MAX / 2).cost_between(Rank::from(0), Rank::from(2)));
+        assert_eq!(None, CostCurve::Quadratic(1).cost_between(Rank::from(i32::MAX - 1), Rank::from(i32::MAX)));
+    }
+
+    #[test]
+    fn caps_and_prerequisites() {
+        let rules = Advancement::new(CostCurve::Linear(1))
+            .with_cap_on(StatBase::Dex, 1)
+            .with_prerequisite(Prerequisite::Stat { stat: StatBase::Dex, min: 12 });
+        let mut stats = StatBlock::new();
+        let skills = SkillList::new();
+        assert_eq!(Err(AdvanceError::Prerequisite(Prerequisite::Stat { stat: StatBase::Dex, min: 12 })),
+                   rules.check(Rank::NONE, None, &stats, &skills));
+        stats.set(Stat::Dex { val: 12 });
+        // cap at +1 modifier +1
+        assert_eq!(Ok((2, 0)), rules.check(Rank::from(1), None, &stats, &skills));
+        assert_eq!(Err(AdvanceError::Capped { rank: 3, cap: 2 }), rules.check(Rank::from(2), Some(StatBase::Str), &stats, &skills));
+    }
+
+    #[test]
+    fn caps_follow_the_governing_stat() {
+        let rules = Advancement::new(CostCurve::Linear(1)).with_cap(0);
+        let mut stats = StatBlock::new();
+        stats.set(Stat::Str { val: 14 });
+        let mut skills = SkillList::new();
+        skills.learn(&Skill::new("climbing", StatBase::Str, "athletics"));
+        skills.learn(&Skill::new("lore", StatBase::Int, "knowledge"));
+        let mut ledger = Ledger::new(100);
+        // STR +2 caps climbing at 2, INT +0 caps lore at 0
+        ledger.raise_skill(&rules, &mut skills, "climbing", &stats).unwrap();
+        ledger.raise_skill(&rules, &mut skills, "climbing", &stats).unwrap();
+        assert_eq!(Err(AdvanceError::Capped { rank: 3, cap: 2 }), ledger.raise_skill(&rules, &mut skills, "climbing", &stats).map(|_| ()));
+        assert_eq!(Err(AdvanceError::Capped { rank: 1, cap: 0 }), ledger.raise_skill(&rules, &mut skills, "lore", &stats).map(|_| ()));
+        // an explicit stat overrides the governing one
+        let by_str = Advancement::new(CostCurve::Linear(1)).with_cap_on(StatBase::Str, 0);
+        ledger.raise_skill(&by_str, &mut skills, "lore", &stats).unwrap();
+        // no governing stat, no cap
+        let mut plain = Rank::NONE;
+        ledger.raise(&rules, "luck", &mut plain, &stats, &skills).unwrap();
+        let json = serde_json::to_string(&rules).unwrap();
+        assert_eq!(rules, serde_json::from_str(&json).unwrap());
+        let old: Advancement = serde_json::from_str(r#"{ "cost": { "linear": 1 }, "cap": { "stat": "Dex", "offset": 1 } }"#).unwrap();
+        assert_eq!(Some(Cap { stat: Some(StatBase::Dex), offset: 1 }), old.cap);
+    }
+
+    #[test]
+    fn top_of_the_range() {
+        let stats = StatBlock::new();
+        let skills = SkillList::new();
+        let rules = Advancement::new(CostCurve::Linear(0));
+        assert_eq!(Ok((0, 0)), rules.check(Rank::from(i32::MAX - 1), None, &stats, &skills));
+        assert_eq!(Err(AdvanceError::NoCost(i32::MAX)), rules.check(Rank::from(i32::MAX), None, &stats, &skills));
+        let mut top = Rank::from(i32::MAX);
+        assert_eq!(Err(AdvanceError::NoCost(i32::MAX)), Ledger::new(0).raise(&rules, "luck", &mut top, &stats, &skills).map(|_| ()));
+        assert_eq!(Err(AdvanceError::NoCost(i32::MAX)), Ledger::new(0).commit("luck", &mut top, 0, 0).map(|_| ()));
+        assert_eq!(Rank::from(i32::MAX), top);
+        let capped = Advancement::new(CostCurve::Linear(0)).with_cap(i32::MAX);
+        assert_eq!(Ok((0, 0)), capped.check(Rank::from(i32::MAX - 1), Some(StatBase::Dex), &stats, &skills));
+    }
+
+    #[test]
+    fn ledger() {
+        let rules = Advancement::new(CostCurve::Linear(10))
+            .with_training(CostCurve::Linear(7))
+            .with_prerequisite(Prerequisite::Skill { name: "riding".into(), rank: 1 });
+        let stats = StatBlock::new();
+        let mut skills = SkillList::new();
+        skills.learn(&Skill::new("mounted combat", StatBase::Str, "combat"));
+        let mut ledger = Ledger::new(25);
+        assert!(matches!(ledger.raise_skill(&rules, &mut skills, "mounted combat", &stats), Err(AdvanceError::Prerequisite(_))));
+        *skills.learn(&Skill::new("riding", StatBase::Dex, "athletics")).rank_mut() += 1;
+        assert_eq!("mounted combat 0 → 1: 10 XP, 7 days of training",
+                   ledger.raise_skill(&rules, &mut skills, "mounted combat", &stats).unwrap().to_string());
+        assert_eq!(Err(AdvanceError::NotEnoughXp { needed: 20, available: 15 }),
+                   ledger.raise_skill(&rules, &mut skills, "mounted combat", &stats).map(|_| ()));
+        ledger.grant(5);
+        ledger.raise_skill(&rules, &mut skills, "mounted combat", &stats).unwrap();
+        assert_eq!(30, ledger.spent());
+        assert_eq!(0, ledger.available());
+        assert_eq!(2, ledger.purchases().len());
+        assert_eq!(Rank::from(2), skills.get("mounted combat").unwrap().rank());
+        let mut plain = Rank::NONE;
+        ledger.grant(10);
+        ledger.raise(&Advancement::new(CostCurve::Linear(10)), "luck", &mut plain, &stats, &skills).unwrap();
+        assert_eq!(Rank::from(1), plain);
+        ledger.grant(u32::MAX);
+        ledger.grant(1);
+        assert_eq!(u32::MAX, ledger.available());
+    }
+}
@@ -0,0 +1,204 @@
+//! Rank descriptors - human readable labels for [Rank] values.
+//!
+//! A [RankDescriptors] table maps rank thresholds to labels, e.g. 0 = Untrained,
+//! 3 = Average, 6 = Expert, 10 = Legendary. A rank gets the label of the highest
+//! threshold it reaches (ranks below all thresholds get the lowest one).
+//!
+//! Tables can be loaded per game system (and language) from JSON:
+//!
+//! ```
+//! use rpgassist::{details::DetailedDisplay, ranking::{Rank, descriptor::RankDescriptors}};
+//!
+//! assert_eq!("Expert", Rank::from(7).explain());
+//! let fi = RankDescriptors::from_json(r#"{
+//!     "name": "fi",
+//!     "ranks": { "0": "Kouluttamaton", "3": "Keskiverto", "6": "Asiantuntija", "10": "Legendaarinen" }
+//! }"#).unwrap();
+//! assert_eq!("Asiantuntija", Rank::from(7).explain_with(&fi));
+//! {
+//!     let _guard = fi.activate();
+//!     assert_eq!("Asiantuntija", Rank::from(7).detailed().to_string());
+//! }
+//! assert_eq!("Expert", Rank::from(7).detailed().to_string());
+//! ```
+use std::{collections::BTreeMap, fmt::Display, path::Path, rc::Rc, sync::OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ranking::Rank, scoped::{self, ScopeGuard, Scoped}};
+
+/// Failure to load [RankDescriptors].
+#[derive(Debug)]
+pub enum DescriptorError {
+    /// Reading the descriptor data failed.
+    Io(std::io::Error),
+    /// JSON didn't parse as descriptors.
+    Json(serde_json::Error),
+    /// No descriptors at all.
+    Empty,
+}
+
+impl Display for DescriptorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Cannot read rank descriptors: {e}"),
+            Self::Json(e) => write!(f, "Cannot parse rank descriptors: {e}"),
+            Self::Empty => write!(f, "No rank descriptors given"),
+        }
+    }
+}
+
+impl std::error::Error for DescriptorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Json(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for DescriptorError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for DescriptorError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+/// Rank threshold → label table, never empty.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct RankDescriptors {
+    name: String,
+    ranks: BTreeMap<i32, String>,
+}
+
+/// [RankDescriptors] as is, before validation.
+#[derive(Deserialize)]
+struct RawDescriptors {
+    #[serde(default)]
+    name: String,
+    ranks: BTreeMap<i32, String>,
+}
+
+impl<'de> Deserialize<'de> for RankDescriptors {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawDescriptors::deserialize(deserializer)?;
+        Self::new(&raw.name, raw.ranks).map_err(serde::de::Error::custom)
+    }
+}
+
+thread_local! {
+    static ACTIVE: Scoped<RankDescriptors> = Scoped::new(RankDescriptors::builtin().clone());
+}
+
+/// Deactivates [activated][RankDescriptors::activate] [RankDescriptors] when dropped.
+pub type RankDescriptorsGuard = ScopeGuard<RankDescriptors>;
+
+impl RankDescriptors {
+    /// Make a new descriptor table from `(threshold, label)` pairs.
+    ///
+    /// # Returns
+    /// **a)** the descriptors, or
+    /// **b)** [DescriptorError::Empty] if there's no `ranks`.
+    pub fn new<S: Into<String>>(name: &str, ranks: impl IntoIterator<Item = (i32, S)>) -> Result<Self, DescriptorError> {
+        let ranks: BTreeMap<i32, String> = ranks.into_iter().map(|(r, l)| (r, l.into())).collect();
+        if ranks.is_empty() {
+            return Err(DescriptorError::Empty)
+        }
+        Ok(Self { name: name.into(), ranks })
+    }
+
+    /// Get the bundled (English) descriptors.
+    pub fn builtin() -> &'static RankDescriptors {
+        static BUILTIN: OnceLock<RankDescriptors> = OnceLock::new();
+        BUILTIN.get_or_init(|| Self::from_json(include_str!("../../data/ranks/en.json"))
+            .expect("Bundled rank descriptors are broken?!"))
+    }
+
+    /// Load descriptors from JSON.
+    pub fn from_json(json: &str) -> Result<Self, DescriptorError> {
+        let raw: RawDescriptors = serde_json::from_str(json)?;
+        Self::new(&raw.name, raw.ranks)
+    }
+
+    /// Load descriptors from a JSON file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, DescriptorError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Get the name of the table, e.g. language code.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the label for `rank`.
+    pub fn describe(&self, rank: Rank) -> &str {
+        self.ranks.range(..=rank.value()).next_back()
+            .or_else(|| self.ranks.first_key_value())
+            .map(|(_, label)| label.as_str())
+            .expect("descriptors are never empty")
+    }
+
+    /// Iterate through `(threshold, label)` pairs, lowest first.
+    pub fn iter(&self) -> impl Iterator<Item = (i32, &str)> {
+        self.ranks.iter().map(|(r, l)| (*r, l.as_str()))
+    }
+
+    /// Make these the active descriptors of the current thread, until the returned guard is dropped.
+    pub fn activate(self) -> RankDescriptorsGuard {
+        scoped::activate(&ACTIVE, self)
+    }
+
+    /// Get the active descriptors of the current thread.
+    pub fn active() -> Rc<RankDescriptors> {
+        ACTIVE.with(|a| a.active())
+    }
+}
+
+#[cfg(test)]
+mod descriptor_tests {
+    use super::*;
+
+    #[test]
+    fn builtin_thresholds() {
+        let d = RankDescriptors::builtin();
+        assert_eq!("Untrained", d.describe(Rank::from(-3)));
+        assert_eq!("Untrained", d.describe(Rank::NONE));
+        assert_eq!("Novice", d.describe(Rank::from(2)));
+        assert_eq!("Average", d.describe(Rank::AVERAGE));
+        assert_eq!("Expert", d.describe(Rank::from(6)));
+        assert_eq!("Legendary", d.describe(Rank::from(42)));
+    }
+
+    #[test]
+    fn custom_tables() {
+        let d = RankDescriptors::new("tiny", [(2, "meh"), (5, "ok")]).unwrap();
+        assert_eq!("meh", d.describe(Rank::NONE));
+        assert_eq!("ok", d.describe(Rank::from(5)));
+        assert_eq!(vec![(2, "meh"), (5, "ok")], d.iter().collect::<Vec<_>>());
+        let _g = d.activate();
+        assert_eq!("ok", Rank::from(9).explain());
+        assert!(matches!(RankDescriptors::from_json(r#"{"ranks": {}}"#), Err(DescriptorError::Empty)));
+        assert!(matches!(RankDescriptors::from_json(r#"{"ranks": {"x": "y"}}"#), Err(DescriptorError::Json(_))));
+        assert!(serde_json::from_str::<RankDescriptors>(r#"{"ranks": {}}"#).is_err());
+        assert!(matches!(RankDescriptors::new("none", Vec::<(i32, &str)>::new()), Err(DescriptorError::Empty)));
+    }
+
+    #[test]
+    fn explicit_and_out_of_order() {
+        let a = RankDescriptors::new("a", [(0, "a")]).unwrap();
+        let b = RankDescriptors::new("b", [(0, "b")]).unwrap();
+        assert_eq!("b", Rank::NONE.explain_with(&b));
+        let ga = a.activate();
+        let gb = b.activate();
+        drop(ga);
+        assert_eq!("b", Rank::NONE.explain());
+        drop(gb);
+        assert_eq!("Untrained", Rank::NONE.explain());
+    }
+}
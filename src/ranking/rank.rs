@@ -24,25 +24,23 @@
 //! 
 //! [Rank] internal value defaults to `0` (zero).
 //! 
+//! # `DetailedDisplay`
+//! 
+//! Uses [Rank::explain], i.e. the active [RankDescriptors].
+//! 
 use std::ops::{Add, AddAssign, Sub, SubAssign};
 
 use serde::{Deserialize, Serialize};
 use paste::paste;
 
-use crate::details::DetailedDisplay;
+use crate::{details::DetailedDisplay, ranking::descriptor::RankDescriptors};
 
 /// Generic "rank" for various things, e.g. stats, skills, etc.
-#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd, Default)]
 pub struct Rank {
     value: i32
 }
 
-impl Default for Rank {
-    fn default() -> Self {
-        Rank { value: 0 }
-    }
-}
-
 /// A trait for anything with 'rank'.
 pub trait IsRanked {
     /// Get current [Rank].
@@ -75,29 +73,36 @@ define_isranked_for_prim!(64);
 define_isranked_for_prim!(128);
 define_isranked_for_prim!(size);
 
+impl IsRanked for Rank {
+    fn rank(&self) -> Rank {
+        *self
+    }
+
+    fn rank_mut(&mut self) -> &mut Rank {
+        self
+    }
+}
+
 impl Rank {
     /// Make a new rank.
     fn new(value: i32) -> Self {
         Self { value }
     }
 
-    /// Get a somewhat detailed rank description.
-    fn explain(&self) -> &'static str {
-        //TODO: add detail to rank explains.
-        match &self.value {
-            ..=0 => "Rank 0",
-            1 => "Rank 1",
-            2 => "Rank 2",
-            3 => "Rank 3",
-            4 => "Rank 4",
-            5 => "Rank 5",
-            6 => "Rank 6",
-            7 => "Rank 7",
-            8 => "Rank 8",
-            9 => "Rank 9",
-            10 => "Rank 10",
-            _ => "Rank 11+",
-        }
+    /// Get the numeric value of the rank.
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    /// Get a description of the rank, e.g. "Expert", as per the
+    /// [active][RankDescriptors::active] [descriptors][RankDescriptors].
+    pub fn explain(&self) -> String {
+        self.explain_with(&RankDescriptors::active())
+    }
+
+    /// Get a description of the rank as per the given `descriptors`.
+    pub fn explain_with(&self, descriptors: &RankDescriptors) -> String {
+        descriptors.describe(*self).to_string()
     }
 
     pub const AVERAGE: Rank = Rank { value: 3 };
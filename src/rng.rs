@@ -0,0 +1,191 @@
+//! Random number sources for anything that rolls dice.
+//!
+//! # `DiceRng`
+//!
+//! The trait every generator in the crate rolls through. Functions like
+//! [Gender::random][crate::gender::Gender::random] use the [ChaosRng] (i.e.
+//! [dicebag]'s global chaotic dice) while their `*_with(rng)` counterparts
+//! accept any [DiceRng] instead.
+//!
+//! Generators also hand each of their decisions over to [DiceRng::record],
+//! which does nothing unless the RNG is wrapped into an
+//! [AuditedRng][crate::audit::AuditedRng].
+//!
+//! # `SeededRng`
+//!
+//! Deterministic, seedable source. Feed the very same seed in and you'll get
+//! the very same rolls out, e.g. for tests or replaying a game session.
+//!
+//! ```
+//! use rpgassist::{gender::Gender, rng::SeededRng};
+//!
+//! let mut a = SeededRng::new(42);
+//! let mut b = SeededRng::new(42);
+//! assert_eq!(Gender::random_with(&mut a), Gender::random_with(&mut b));
+//! ```
+use dicebag::DiceExt;
+
+use crate::audit::RollRecord;
+
+/// A trait for any source of (pseudo)random dice rolls.
+pub trait DiceRng {
+    /// Roll a single `sides`-sided die, i.e. a value within `1..=sides`.
+    ///
+    /// # Panic
+    /// A zero-sided die will cause a panic.
+    fn roll_die(&mut self, sides: u32) -> u32;
+
+    /// Roll `count` dice of `sides` sides each and sum them up.
+    fn roll(&mut self, count: u32, sides: u32) -> i32 {
+        (0..count).map(|_| self.roll_die(sides) as i32).sum()
+    }
+
+    /// Is anyone interested in [records][DiceRng::record]?
+    ///
+    /// Generators check this before bothering to put together a [RollRecord].
+    fn is_recording(&self) -> bool {
+        false
+    }
+
+    /// Record a roll - does nothing by default.
+    fn record(&mut self, _record: RollRecord) {}
+}
+
+impl<R: DiceRng + ?Sized> DiceRng for &mut R {
+    fn roll_die(&mut self, sides: u32) -> u32 {
+        (**self).roll_die(sides)
+    }
+
+    fn is_recording(&self) -> bool {
+        (**self).is_recording()
+    }
+
+    fn record(&mut self, record: RollRecord) {
+        (**self).record(record)
+    }
+}
+
+impl DiceRng for Box<dyn DiceRng> {
+    fn roll_die(&mut self, sides: u32) -> u32 {
+        (**self).roll_die(sides)
+    }
+
+    fn is_recording(&self) -> bool {
+        (**self).is_recording()
+    }
+
+    fn record(&mut self, record: RollRecord) {
+        (**self).record(record)
+    }
+}
+
+/// The default [DiceRng] - rolls via [dicebag]'s global (and unseedable) dice.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ChaosRng;
+
+impl DiceRng for ChaosRng {
+    fn roll_die(&mut self, sides: u32) -> u32 {
+        if sides == 0 { panic!("Zero-sided die? Not rolling that…") }
+        1_u32.d(sides as usize)
+    }
+}
+
+/// A deterministic [DiceRng] (xoshiro256** seeded via splitmix64).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeededRng {
+    seed: u64,
+    state: [u64; 4],
+}
+
+impl SeededRng {
+    /// Make a new RNG from the given `seed`.
+    pub fn new(seed: u64) -> Self {
+        let mut sm = seed;
+        let mut splitmix = || {
+            sm = sm.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = sm;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        Self { seed, state: [splitmix(), splitmix(), splitmix(), splitmix()] }
+    }
+
+    /// Get the seed this RNG was made from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Get next raw 64-bit value.
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+}
+
+impl DiceRng for SeededRng {
+    fn roll_die(&mut self, sides: u32) -> u32 {
+        if sides == 0 { panic!("Zero-sided die? Not rolling that…") }
+        // Lemire's unbiased range reduction.
+        let sides = sides as u64;
+        let mut m = (self.next_u64() as u128) * (sides as u128);
+        if (m as u64) < sides {
+            let t = sides.wrapping_neg() % sides;
+            while (m as u64) < t {
+                m = (self.next_u64() as u128) * (sides as u128);
+            }
+        }
+        ((m >> 64) as u32) + 1
+    }
+}
+
+#[cfg(test)]
+mod rng_tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_rolls() {
+        let mut a = SeededRng::new(867);
+        let mut b = SeededRng::new(867);
+        for _ in 0..1000 {
+            assert_eq!(a.roll(3, 6), b.roll(3, 6));
+        }
+    }
+
+    #[test]
+    fn different_seed_different_rolls() {
+        let mut a = SeededRng::new(1);
+        let mut b = SeededRng::new(2);
+        let a: Vec<u32> = (0..32).map(|_| a.roll_die(100)).collect();
+        let b: Vec<u32> = (0..32).map(|_| b.roll_die(100)).collect();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn rolls_stay_within_sides() {
+        let mut rng = SeededRng::new(0);
+        for sides in 1..=20 {
+            for _ in 0..200 {
+                let r = rng.roll_die(sides);
+                assert!((1..=sides).contains(&r));
+            }
+        }
+    }
+
+    #[test]
+    fn chaos_rolls_stay_within_sides() {
+        let mut rng = ChaosRng;
+        for _ in 0..200 {
+            let r = rng.roll_die(6);
+            assert!((1..=6).contains(&r));
+        }
+    }
+}
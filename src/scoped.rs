@@ -0,0 +1,94 @@
+//! Per-thread scoped "active" values, e.g. the active [RuleSet][crate::stat::rules::RuleSet].
+//!
+//! A [Scoped] keeps a stack of activated values on top of a fallback. Each
+//! activation hands out a [ScopeGuard] which removes exactly *its* value when
+//! dropped - so guards dropped out of order don't resurrect stale values, the
+//! most recently activated value still around stays active.
+use std::{cell::{Cell, RefCell}, marker::PhantomData, rc::Rc, thread::LocalKey};
+
+/// Stack of activated values over a fallback - meant to live in a `thread_local!`.
+pub struct Scoped<T: 'static> {
+    fallback: Rc<T>,
+    stack: RefCell<Vec<(usize, Rc<T>)>>,
+    next_id: Cell<usize>,
+}
+
+impl<T: 'static> Scoped<T> {
+    /// A new stack with `fallback` active when nothing else is.
+    pub fn new(fallback: T) -> Self {
+        Self { fallback: Rc::new(fallback), stack: RefCell::new(vec![]), next_id: Cell::new(0) }
+    }
+
+    /// Get the active value.
+    pub fn active(&self) -> Rc<T> {
+        self.stack.borrow().last().map_or_else(|| self.fallback.clone(), |(_, v)| v.clone())
+    }
+
+    fn push(&self, value: T) -> usize {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        self.stack.borrow_mut().push((id, Rc::new(value)));
+        id
+    }
+
+    fn remove(&self, id: usize) {
+        self.stack.borrow_mut().retain(|(i, _)| *i != id);
+    }
+}
+
+/// Activate `value` in the thread-local `key`, until the returned guard is dropped.
+pub fn activate<T: 'static>(key: &'static LocalKey<Scoped<T>>, value: T) -> ScopeGuard<T> {
+    let id = key.with(|s| s.push(value));
+    ScopeGuard { key, id, _not_send: PhantomData }
+}
+
+/// Deactivates a value [activated][activate] in a [Scoped] when dropped.
+#[must_use = "the value is deactivated as soon as the guard is dropped"]
+pub struct ScopeGuard<T: 'static> {
+    key: &'static LocalKey<Scoped<T>>,
+    id: usize,
+    /// Guards belong to the thread they were made in.
+    _not_send: PhantomData<Rc<()>>,
+}
+
+impl<T: 'static> Drop for ScopeGuard<T> {
+    fn drop(&mut self) {
+        // the thread-local may be gone already if we're dropped during thread teardown
+        let _ = self.key.try_with(|s| s.remove(self.id));
+    }
+}
+
+#[cfg(test)]
+mod scoped_tests {
+    use super::*;
+
+    thread_local! {
+        static NAME: Scoped<&'static str> = Scoped::new("fallback");
+    }
+
+    fn active() -> &'static str {
+        *NAME.with(|s| s.active())
+    }
+
+    #[test]
+    fn nested_guards() {
+        let a = activate(&NAME, "a");
+        {
+            let _b = activate(&NAME, "b");
+            assert_eq!("b", active());
+        }
+        assert_eq!("a", active());
+        drop(a);
+        assert_eq!("fallback", active());
+    }
+
+    #[test]
+    fn out_of_order_drops() {
+        let a = activate(&NAME, "a");
+        let b = activate(&NAME, "b");
+        drop(a);
+        assert_eq!("b", active());
+        drop(b);
+        assert_eq!("fallback", active());
+    }
+}
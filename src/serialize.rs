@@ -9,6 +9,13 @@
 //! Deserializer (no serializer) for creating a `Vec<String>` from either
 //! **a)** a single `String`
 //! **b)** array of `String`s.
+//! 
+//! # `serial_range`
+//! 
+//! (De)serialize `RangeInclusive<i32>` from/as either
+//! **a)** a single number, `5`
+//! **b)** a string, `"5-6"`, or
+//! **c)** an array, `[5, 6]`.
 /// Ordering (de)serializer functions live here.
 pub mod serial_ordering {
     use std::cmp::Ordering;
@@ -21,7 +28,7 @@ pub mod serial_ordering {
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        match s.to_lowercase().as_str().chars().nth(0) {
+        match s.to_lowercase().as_str().chars().next() {
             Some('l') => Ok(Ordering::Less),
             Some('e') => Ok(Ordering::Equal),
             Some('g') => Ok(Ordering::Greater),
@@ -93,4 +100,56 @@ pub mod serial_uf64 {
             Ok(val)
         }
     }
-}
\ No newline at end of file
+}
+/// Inclusive i32 range (de)serializer functions live here.
+pub mod serial_range {
+    use std::ops::RangeInclusive;
+
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    /// Parse e.g. `"5"`, `"5-6"`, or `"-3--1"` into a range.
+    pub fn parse(s: &str) -> Option<RangeInclusive<i32>> {
+        let s = s.trim();
+        // skip 1st char so that a leading minus isn't mistaken for a separator…
+        match s.char_indices().skip(1).find(|(_, c)| *c == '-') {
+            None => s.parse().ok().map(|v| v..=v),
+            Some((i, _)) => {
+                let start = s[..i].trim().parse().ok()?;
+                let end = s[i+1..].trim().parse().ok()?;
+                Some(start..=end)
+            }
+        }
+    }
+
+    /// 'RangeInclusive' deserializer.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<RangeInclusive<i32>, D::Error>
+    where D: Deserializer<'de> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RangeHalp {
+            N(i32),
+            S(String),
+            A(Vec<i32>),
+        }
+
+        match RangeHalp::deserialize(deserializer)? {
+            RangeHalp::N(v) => Ok(v..=v),
+            RangeHalp::S(s) => parse(&s).ok_or_else(|| Error::custom(format!("Cannot parse range from '{s}'"))),
+            RangeHalp::A(v) => match v.as_slice() {
+                [v] => Ok(*v..=*v),
+                [s, e] => Ok(*s..=*e),
+                _ => Err(Error::custom(format!("Range array needs 1 or 2 values, got {v:?} instead…")))
+            }
+        }
+    }
+
+    /// 'RangeInclusive' serializer.
+    pub fn serialize<S>(range: &RangeInclusive<i32>, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        if range.start() == range.end() {
+            serializer.serialize_i32(*range.start())
+        } else {
+            serializer.serialize_str(&format!("{}-{}", range.start(), range.end()))
+        }
+    }
+}
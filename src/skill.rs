@@ -0,0 +1,269 @@
+//! Skills - [Skill] definitions and per-character [SkillList]s.
+//!
+//! Effective value of a skill for rolls is its [Rank] plus the
+//! [modifier][HasModifier] of its governing [Stat], and a bit extra when a
+//! matching specialization is known.
+//!
+//! ```
+//! use rpgassist::{ranking::rank::IsRanked, skill::{Skill, SkillList}, stat::{Stat, StatBase, StatBlock}};
+//!
+//! let climb = Skill::new("climbing", StatBase::Str, "athletics")
+//!     .with_specialization("rock")
+//!     .with_specialization("ice");
+//! let mut skills = SkillList::new();
+//! *skills.learn(&climb).rank_mut() += 2;
+//! let mut stats = StatBlock::new();
+//! stats.set(Stat::Str { val: 14 });
+//! assert_eq!(4, skills.effective(&climb, &stats));
+//! ```
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ext::IsNamed, modifier::HasModifier, ranking::{rank::IsRanked, Rank}, stat::{Stat, StatBase, StatBlock}};
+
+/// Bonus for having a matching specialization.
+pub const SPECIALIZATION_BONUS: i32 = 2;
+
+/// Skill definition.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Skill {
+    name: String,
+    /// The stat governing the skill.
+    pub governing: StatBase,
+    /// E.g. `"athletics"`, `"lore"`, `"combat"`.
+    #[serde(default)]
+    pub category: String,
+    /// Rank when untrained.
+    #[serde(default)]
+    pub default: Rank,
+    #[serde(default)]
+    pub specializations: Vec<String>,
+}
+
+impl Skill {
+    /// A new skill, untrained rank of zero.
+    pub fn new(name: &str, governing: StatBase, category: &str) -> Self {
+        Self { name: name.into(), governing, category: category.into(), default: Rank::NONE, specializations: vec![] }
+    }
+
+    /// Set the untrained rank.
+    pub fn with_default(mut self, rank: Rank) -> Self {
+        self.default = rank;
+        self
+    }
+
+    /// Add a specialization.
+    pub fn with_specialization(mut self, spec: &str) -> Self {
+        self.specializations.push(spec.into());
+        self
+    }
+}
+
+impl IsNamed for Skill {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// A skill as known by a character.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct LearnedSkill {
+    pub skill: Skill,
+    rank: Rank,
+    /// Specializations picked up.
+    #[serde(default)]
+    pub specialized: BTreeSet<String>,
+}
+
+impl LearnedSkill {
+    /// Learn a `skill`, starting at its untrained rank.
+    pub fn new(skill: &Skill) -> Self {
+        Self { skill: skill.clone(), rank: skill.default, specialized: BTreeSet::new() }
+    }
+
+    /// Pick up a specialization.
+    ///
+    /// Returns `false` if the skill has no such specialization.
+    pub fn specialize(&mut self, spec: &str) -> bool {
+        if !self.skill.specializations.iter().any(|s| s == spec) {
+            return false
+        }
+        self.specialized.insert(spec.into());
+        true
+    }
+
+    /// Get the governing stat from `stats`.
+    pub fn governing<'a>(&self, stats: &'a StatBlock) -> &'a Stat {
+        stats.get(self.skill.governing)
+    }
+
+    /// Get the effective value for rolls, i.e. rank + governing stat modifier.
+    pub fn effective(&self, stats: &StatBlock) -> i32 {
+        self.rank.value().saturating_add(self.governing(stats).modifier())
+    }
+
+    /// Get the effective value for rolls involving `spec`.
+    pub fn effective_for(&self, spec: &str, stats: &StatBlock) -> i32 {
+        self.effective(stats).saturating_add(if self.specialized.contains(spec) { SPECIALIZATION_BONUS } else { 0 })
+    }
+}
+
+impl IsNamed for LearnedSkill {
+    fn name(&self) -> &str {
+        self.skill.name()
+    }
+}
+
+impl IsRanked for LearnedSkill {
+    fn rank(&self) -> Rank {
+        self.rank
+    }
+
+    fn rank_mut(&mut self) -> &mut Rank {
+        &mut self.rank
+    }
+}
+
+/// Skills of a character - one of each name.
+#[derive(Debug, Serialize, Clone, PartialEq, Default)]
+#[serde(transparent)]
+pub struct SkillList {
+    skills: Vec<LearnedSkill>,
+}
+
+impl<'de> Deserialize<'de> for SkillList {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let skills = Vec::<LearnedSkill>::deserialize(deserializer)?;
+        for (i, s) in skills.iter().enumerate() {
+            if skills[..i].iter().any(|o| o.name() == s.name()) {
+                return Err(serde::de::Error::custom(format!("skill '{}' listed more than once", s.name())))
+            }
+        }
+        Ok(Self { skills })
+    }
+}
+
+impl SkillList {
+    /// An empty list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Learn a `skill` (if not known already) and get it.
+    ///
+    /// If a skill of the same name is known with a different definition, the definition
+    /// gets updated - keeping the rank, and those specializations the new one still has.
+    pub fn learn(&mut self, skill: &Skill) -> &mut LearnedSkill {
+        let idx = match self.skills.iter().position(|s| s.name() == skill.name()) {
+            Some(idx) => {
+                let known = &mut self.skills[idx];
+                if known.skill != *skill {
+                    known.skill = skill.clone();
+                    known.specialized.retain(|spec| skill.specializations.contains(spec));
+                }
+                idx
+            },
+            None => {
+                self.skills.push(LearnedSkill::new(skill));
+                self.skills.len() - 1
+            }
+        };
+        &mut self.skills[idx]
+    }
+
+    /// Forget a skill.
+    pub fn forget(&mut self, name: &str) -> Option<LearnedSkill> {
+        let idx = self.skills.iter().position(|s| s.name() == name)?;
+        Some(self.skills.remove(idx))
+    }
+
+    /// Get a skill by name.
+    pub fn get(&self, name: &str) -> Option<&LearnedSkill> {
+        self.skills.iter().find(|s| s.name() == name)
+    }
+
+    /// Get a mutable skill by name.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut LearnedSkill> {
+        self.skills.iter_mut().find(|s| s.name() == name)
+    }
+
+    /// Iterate through the skills.
+    pub fn iter(&self) -> impl Iterator<Item = &LearnedSkill> {
+        self.skills.iter()
+    }
+
+    /// Get the rank of `skill` - its untrained rank if not learned.
+    pub fn rank_of(&self, skill: &Skill) -> Rank {
+        self.get(skill.name()).map_or(skill.default, |s| s.rank)
+    }
+
+    /// Get the effective value of `skill` for rolls, learned or not.
+    pub fn effective(&self, skill: &Skill, stats: &StatBlock) -> i32 {
+        match self.get(skill.name()) {
+            Some(learned) => learned.effective(stats),
+            None => LearnedSkill::new(skill).effective(stats)
+        }
+    }
+}
+
+#[cfg(test)]
+mod skill_tests {
+    use super::*;
+
+    fn lockpicking() -> Skill {
+        Skill::new("lockpicking", StatBase::Dex, "thievery")
+            .with_default(Rank::from(-2))
+            .with_specialization("padlocks")
+    }
+
+    #[test]
+    fn untrained_and_learned() {
+        let lp = lockpicking();
+        let mut stats = StatBlock::new();
+        stats.set(Stat::Dex { val: 14 });
+        let mut skills = SkillList::new();
+        assert_eq!(0, skills.effective(&lp, &stats));
+        *skills.learn(&lp).rank_mut() = Rank::AVERAGE;
+        assert_eq!(5, skills.effective(&lp, &stats));
+        // learning again keeps the rank
+        assert_eq!(Rank::AVERAGE, skills.learn(&lp).rank());
+        assert_eq!(1, skills.iter().count());
+        assert!(skills.forget("lockpicking").is_some());
+        assert_eq!(Rank::from(-2), skills.rank_of(&lp));
+    }
+
+    #[test]
+    fn specializations() {
+        let lp = lockpicking();
+        let stats = StatBlock::new();
+        let mut skills = SkillList::new();
+        let s = skills.learn(&lp);
+        assert!(!s.specialize("safes"));
+        assert!(s.specialize("padlocks"));
+        assert_eq!(-2, s.effective_for("safes", &stats));
+        assert_eq!(0, s.effective_for("padlocks", &stats));
+        let json = serde_json::to_string(&skills).unwrap();
+        assert_eq!(skills, serde_json::from_str(&json).unwrap());
+        let twice = format!("[{0},{0}]", &json[1..json.len() - 1]);
+        assert!(serde_json::from_str::<SkillList>(&twice).is_err());
+    }
+
+    #[test]
+    fn redefined_skills() {
+        let lp = lockpicking();
+        let mut stats = StatBlock::new();
+        stats.set(Stat::Int { val: 16 });
+        let mut skills = SkillList::new();
+        let s = skills.learn(&lp);
+        *s.rank_mut() = Rank::AVERAGE;
+        assert!(s.specialize("padlocks"));
+        let lp2 = Skill::new("lockpicking", StatBase::Int, "thievery").with_specialization("safes");
+        let s = skills.learn(&lp2);
+        assert_eq!(StatBase::Int, s.skill.governing);
+        assert_eq!(Rank::AVERAGE, s.rank());
+        assert!(s.specialized.is_empty());
+        assert_eq!(1, skills.iter().count());
+        assert_eq!(6, skills.effective(&lp2, &stats));
+    }
+}
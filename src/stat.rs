@@ -9,12 +9,26 @@
 //! * **Mag**ical Aptitude
 //! * **Str**ength (physical)
 //! * **Will** (strength of one's mind)
-use std::{fmt::Display, hash::Hash, ops::{Add, AddAssign, Sub, SubAssign}};
+//!
+//! A full set of them for a character lives in a [StatBlock].
+//!
+//! Value ranges (and clamping), as well as the modifiers stats give, are up to
+//! the [active][rules::RuleSet::active] [rules][rules::RuleSet].
+use std::{fmt::Display, hash::Hash, num::NonZeroU32, ops::{Add, AddAssign, Sub, SubAssign}};
 
 use serde::{Deserialize, Serialize};
 
+use crate::modifier::HasModifier;
+
+mod block;
+pub use block::StatBlock;
+pub mod derived;
+pub mod generate;
+pub mod registry;
+pub mod rules;
+
 /// Core stat types without value payload.
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum StatBase {
     Age, App, Cha, Con, Dex, Int, Mag, Str, Will,
 }
@@ -36,6 +50,91 @@ impl Display for StatBase {
 }
 
 impl StatBase {
+    /// All the stat types, in canonical order.
+    pub const ALL: [StatBase; 9] = [
+        Self::Age, Self::App, Self::Cha, Self::Con, Self::Dex,
+        Self::Int, Self::Mag, Self::Str, Self::Will,
+    ];
+
+    /// Get the id of the stat type in the [registry][registry::StatRegistry], e.g. `"str"`.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Age => "age",
+            Self::App => "app",
+            Self::Cha => "cha",
+            Self::Con => "con",
+            Self::Dex => "dex",
+            Self::Int => "int",
+            Self::Mag => "mag",
+            Self::Str => "str",
+            Self::Will => "will",
+        }
+    }
+
+    /// Get the stat type matching `id`, if it's one of the built-in nine.
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|b| b.id() == id)
+    }
+
+    /// Make a [Stat] of this type with the given `val`ue.
+    pub fn with(&self, val: i32) -> Stat {
+        match self {
+            Self::Age => Stat::Age { val },
+            Self::App => Stat::App { val },
+            Self::Cha => Stat::Cha { val },
+            Self::Con => Stat::Con { val },
+            Self::Dex => Stat::Dex { val },
+            Self::Int => Stat::Int { val },
+            Self::Mag => Stat::Mag { val },
+            Self::Str => Stat::Str { val },
+            Self::Will => Stat::Will { val },
+        }
+    }
+
+    /// Get the classic [modifier curve][rules::ModifierCurve] of the stat type:
+    /// * Con/Dex/Int/Str (average 10) - every 2 points above/below 10 count ±1,
+    /// * App/Cha/Mag/Will (average ±0) - the value itself,
+    /// * Age - none.
+    ///
+    /// See [RuleSet::classic][rules::RuleSet::classic].
+    pub fn curve(&self) -> rules::ModifierCurve {
+        match self {
+            Self::Age => rules::ModifierCurve::None,
+            Self::App |
+            Self::Cha |
+            Self::Mag |
+            Self::Will => rules::ModifierCurve::Linear,
+            Self::Con |
+            Self::Dex |
+            Self::Int |
+            Self::Str => rules::ModifierCurve::Stepped { base: 10, per: NonZeroU32::new(2).unwrap() },
+        }
+    }
+
+    /// Get the classic lowest value the stat type can have, if any.
+    ///
+    /// See [RuleSet::classic][rules::RuleSet::classic].
+    pub fn minimum(&self) -> Option<i32> {
+        match self {
+            Self::App |
+            Self::Cha => None,
+            // These cannot fall below 0…:
+            Self::Age |
+            Self::Int |
+            Self::Mag |
+            Self::Will => Some(0),
+            // These have a min of 1:
+            Self::Con |
+            Self::Dex |
+            Self::Str => Some(1),
+        }
+    }
+
+    /// Clamp `val` to the range of the stat type, as per the [active][rules::RuleSet::active] rules.
+    pub fn clamp_value(&self, val: i32) -> i32 {
+        rules::RuleSet::active().clamp(*self, val)
+    }
+
     /// Generate a sensible 'default' [Stat] instance.
     pub fn default(&self) -> Stat {
         match self {
@@ -99,6 +198,21 @@ impl Stat {
         }
     }
 
+    /// Get a mutable reference to the underlying stat value.
+    fn value_mut(&mut self) -> &mut i32 {
+        match self {
+            Self::Age { val } |
+            Self::App { val } |
+            Self::Cha { val } |
+            Self::Con { val } |
+            Self::Dex { val } |
+            Self::Int { val } |
+            Self::Mag { val } |
+            Self::Str { val } |
+            Self::Will { val }=> val
+        }
+    }
+
     /// Get the base type without value payload.
     pub fn stat_base(&self) -> StatBase {
         StatBase::from(self)
@@ -171,43 +285,18 @@ impl Sub<Stat> for Stat {
 
 impl AddAssign<i32> for Stat {
     fn add_assign(&mut self, rhs: i32) {
-        match self {
-            Self::App { val } |
-            Self::Cha { val }
-                => *val += rhs,
-            // These cannot fall below 0…:
-            Self::Age { val } |
-            Self::Int { val } |
-            Self::Mag { val } |
-            Self::Will { val }
-                => *val = (*val + rhs).max(0),
-            // These have a min of 1:
-            Self::Con { val } |
-            Self::Dex { val } |
-            Self::Str { val }
-                => *val = (*val + rhs).max(1),
-        }
+        let base = self.stat_base();
+        let val = self.value_mut();
+        *val = rules::RuleSet::active().apply(base, *val, rhs)
     }
 }
 
 impl SubAssign<i32> for Stat {
     fn sub_assign(&mut self, rhs: i32) {
-        match self {
-            Self::App { val } |
-            Self::Cha { val }
-                => *val -= rhs,
-            // These cannot fall below 0…:
-            Self::Age { val } |
-            Self::Int { val } |
-            Self::Mag { val } |
-            Self::Will { val }
-                => *val = (*val - rhs).max(0),
-            // These have a min of 1:
-            Self::Con { val } |
-            Self::Dex { val } |
-            Self::Str { val }
-                => *val = (*val - rhs).max(1),
-        }
+        let base = self.stat_base();
+        let val = self.value_mut();
+        // -i32::MIN doesn't fit in an i32; i32::MAX is as close as it gets
+        *val = rules::RuleSet::active().apply(base, *val, rhs.saturating_neg())
     }
 }
 
@@ -229,6 +318,15 @@ impl SubAssign<Stat> for Stat {
     }
 }
 
+impl HasModifier for Stat {
+    /// Modifier for rolls, as per the [curve][rules::ModifierCurve] of the
+    /// [active][rules::RuleSet::active] rules - by default the
+    /// [classic one][StatBase::curve].
+    fn modifier(&self) -> i32 {
+        rules::RuleSet::active().modifier(self.stat_base(), self.value())
+    }
+}
+
 impl Display for Stat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} {:+}", self.stat_base(), self.value())
@@ -239,6 +337,16 @@ impl Display for Stat {
 mod stat_tests {
     use super::*;
 
+    #[test]
+    fn stat_modifiers() {
+        assert_eq!(0, Stat::Str { val: 10 }.modifier());
+        assert_eq!(0, Stat::Str { val: 11 }.modifier());
+        assert_eq!(-1, Stat::Str { val: 9 }.modifier());
+        assert_eq!(3, Stat::Dex { val: 16 }.modifier());
+        assert_eq!(-2, Stat::Cha { val: -2 }.modifier());
+        assert_eq!(0, Stat::Age { val: 80 }.modifier());
+    }
+
     #[test]
     fn add_i32_to_stat() {
         let stat = Stat::Str { val: 10 };
@@ -266,6 +374,8 @@ mod stat_tests {
         let stat = Stat::Str { val: 10 };
         let stat = stat - 2;
         assert_eq!(8, stat.value());
+        let stat = Stat::Cha { val: 0 } - i32::MIN;
+        assert_eq!(i32::MAX, stat.value());
     }
 
     #[test]
@@ -0,0 +1,172 @@
+//! A full set of [Stat]s, one per [StatBase].
+//!
+//! ```
+//! use rpgassist::{details::DetailedDisplay, stat::{StatBase, StatBlock}};
+//!
+//! let mut block = StatBlock::new();
+//! block.update(StatBase::Str, |s| *s += 3);
+//! assert_eq!(13, block.value(StatBase::Str));
+//! assert_eq!(18, block.value(StatBase::Age));// default
+//! println!("{}", block.detailed());
+//! ```
+use std::{collections::BTreeMap, fmt::Display, ops::Index};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{details::DetailedDisplay, stat::{Stat, StatBase}};
+
+/// One [Stat] per [StatBase], in canonical order.
+///
+/// Serializes as a map of stat type → value; missing entries fall back to
+/// [StatBase::default] when deserializing.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(from = "BTreeMap<StatBase, i32>", into = "BTreeMap<StatBase, i32>")]
+pub struct StatBlock {
+    stats: [Stat; 9],
+}
+
+impl Default for StatBlock {
+    fn default() -> Self {
+        Self { stats: StatBase::ALL.map(|b| b.default()) }
+    }
+}
+
+impl StatBlock {
+    /// A block with [default][StatBase::default] values all around.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the [Stat] of the given type.
+    pub fn get(&self, base: StatBase) -> &Stat {
+        &self.stats[base as usize]
+    }
+
+    /// Get the value of the given stat type.
+    pub fn value(&self, base: StatBase) -> i32 {
+        self.get(base).value()
+    }
+
+    /// Set (replace) a [Stat].
+    pub fn set(&mut self, stat: Stat) {
+        let i = stat.stat_base() as usize;
+        self.stats[i] = stat
+    }
+
+    /// Modify a [Stat] in place, e.g. `update(StatBase::Str, |s| *s += 2)`.
+    ///
+    /// The stat stays of the given type - should `f` swap in another type of
+    /// [Stat], only its value is kept.
+    pub fn update<F: FnOnce(&mut Stat)>(&mut self, base: StatBase, f: F) {
+        let stat = &mut self.stats[base as usize];
+        f(stat);
+        *stat = base.with(stat.value())
+    }
+
+    /// Iterate through the stats in canonical order.
+    pub fn iter(&self) -> impl Iterator<Item = &Stat> {
+        self.stats.iter()
+    }
+}
+
+impl Index<StatBase> for StatBlock {
+    type Output = Stat;
+
+    fn index(&self, index: StatBase) -> &Self::Output {
+        self.get(index)
+    }
+}
+
+impl<'a> IntoIterator for &'a StatBlock {
+    type Item = &'a Stat;
+    type IntoIter = std::slice::Iter<'a, Stat>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.stats.iter()
+    }
+}
+
+impl FromIterator<Stat> for StatBlock {
+    /// Collect stats into a block; missing ones get their defaults, later duplicates win.
+    fn from_iter<T: IntoIterator<Item = Stat>>(iter: T) -> Self {
+        let mut block = Self::new();
+        iter.into_iter().for_each(|s| block.set(s));
+        block
+    }
+}
+
+impl From<BTreeMap<StatBase, i32>> for StatBlock {
+    fn from(value: BTreeMap<StatBase, i32>) -> Self {
+        value.into_iter().map(|(b, v)| b.with(v)).collect()
+    }
+}
+
+impl From<StatBlock> for BTreeMap<StatBase, i32> {
+    fn from(value: StatBlock) -> Self {
+        value.iter().map(|s| (s.stat_base(), s.value())).collect()
+    }
+}
+
+impl Display for StatBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let stats: Vec<String> = self.iter().map(|s| s.to_string()).collect();
+        write!(f, "{}", stats.join(", "))
+    }
+}
+
+impl DetailedDisplay for StatBlock {
+    /// One stat per line, e.g. `"STR        12"`.
+    fn detailed_display(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, s) in self.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{:<9} {:>3}", s.stat_base().to_string(), s.value())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod stat_block_tests {
+    use super::*;
+
+    #[test]
+    fn defaults_and_indexing() {
+        let mut b = StatBlock::new();
+        assert_eq!(10, b[StatBase::Dex].value());
+        b.update(StatBase::Dex, |d| *d -= 20);
+        // clamping still applies…
+        assert_eq!(1, b.value(StatBase::Dex));
+        b.set(Stat::Int { val: 15 });
+        assert_eq!(Stat::Int { val: 15 }, b[StatBase::Int]);
+        b.update(StatBase::Int, |i| *i = Stat::Dex { val: 3 });
+        assert_eq!(Stat::Int { val: 3 }, b[StatBase::Int]);
+        assert_eq!(1, b.value(StatBase::Dex));
+    }
+
+    #[test]
+    fn canonical_order() {
+        let b = StatBlock::new();
+        let order: Vec<StatBase> = b.iter().map(|s| s.stat_base()).collect();
+        assert_eq!(StatBase::ALL.to_vec(), order);
+    }
+
+    #[test]
+    fn partial_json_falls_back_to_defaults() {
+        let b: StatBlock = serde_json::from_str(r#"{"Str": 14, "Mag": 3}"#).unwrap();
+        assert_eq!(14, b.value(StatBase::Str));
+        assert_eq!(3, b.value(StatBase::Mag));
+        assert_eq!(10, b.value(StatBase::Con));
+        let json = serde_json::to_string(&b).unwrap();
+        assert_eq!(b, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn detailed_display() {
+        let b = StatBlock::new();
+        let s = b.detailed().to_string();
+        assert_eq!(9, s.lines().count());
+        assert_eq!("STR        10", s.lines().nth(7).unwrap());
+    }
+}
@@ -0,0 +1,615 @@
+//! Derived stats - values computed from [Stat]s (and each other) by formulas,
+//! e.g. hit points `con*2 + str/2`.
+//!
+//! Formulas refer to stats by their [ids][StatBase::id] and to other derived
+//! stats by theirs. Supported are integers, `+ - * /` (integer division, rounding
+//! towards zero), parentheses, and `min(a, b)`/`max(a, b)`. They (de)serialize
+//! as plain strings, so a rule system can ship its derivations as JSON:
+//!
+//! ```
+//! use rpgassist::stat::{StatBase, StatBlock, derived::{Derivations, StatSheet}};
+//!
+//! let derivations = Derivations::from_json(r#"[
+//!     { "id": "hp", "name": "Hit Points", "formula": "con*2 + str/2" },
+//!     { "id": "carry", "name": "Carry Capacity", "formula": "str * 5" }
+//! ]"#).unwrap();
+//! let mut sheet = StatSheet::new(StatBlock::new(), derivations).unwrap();
+//! assert_eq!(Some(25), sheet.derived("hp"));
+//! sheet.update(StatBase::Con, |con| *con += 2).unwrap();
+//! assert_eq!(Some(29), sheet.derived("hp"));
+//! ```
+use std::{collections::BTreeMap, fmt::Display, path::Path, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ext::IsNamed, stat::{Stat, StatBase, StatBlock}};
+
+/// Something wrong with a formula or derivations.
+#[derive(Debug)]
+pub enum FormulaError {
+    /// Reading derivation data failed.
+    Io(std::io::Error),
+    /// JSON didn't parse as derivations.
+    Json(serde_json::Error),
+    /// Formula doesn't parse.
+    Parse(String),
+    /// Formula refers to something that isn't a stat or a derived stat.
+    UnknownName { id: String, name: String },
+    /// Derived stats depend on each other in a loop.
+    Cycle(String),
+    /// Two or more derivations with the same id (or an id shared with a stat).
+    DuplicateId(String),
+    /// Division by zero while evaluating.
+    DivisionByZero(String),
+    /// Value too big (or small) to handle while evaluating.
+    Overflow(String),
+}
+
+impl Display for FormulaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Cannot read derivations: {e}"),
+            Self::Json(e) => write!(f, "Cannot parse derivations: {e}"),
+            Self::Parse(e) => write!(f, "Cannot parse formula: {e}"),
+            Self::UnknownName { id, name } => write!(f, "'{id}' refers to unknown '{name}'"),
+            Self::Cycle(id) => write!(f, "'{id}' depends on itself"),
+            Self::DuplicateId(id) => write!(f, "'{id}' defined more than once"),
+            Self::DivisionByZero(id) => write!(f, "'{id}' divides by zero"),
+            Self::Overflow(id) => write!(f, "'{id}' overflows"),
+        }
+    }
+}
+
+impl std::error::Error for FormulaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Json(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for FormulaError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for FormulaError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+/// Binary operators, by precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add, Sub, Mul, Div,
+}
+
+impl Op {
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Add | Self::Sub => 1,
+            Self::Mul | Self::Div => 2,
+        }
+    }
+}
+
+impl Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+        })
+    }
+}
+
+/// Formula syntax tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Num(i32),
+    /// Stat or derived stat id.
+    Name(String),
+    Neg(Box<Expr>),
+    Bin(Op, Box<Expr>, Box<Expr>),
+    Min(Box<Expr>, Box<Expr>),
+    Max(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn names<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            Self::Num(_) => (),
+            Self::Name(n) => if !out.contains(&n.as_str()) { out.push(n) },
+            Self::Neg(e) => e.names(out),
+            Self::Bin(_, a, b) |
+            Self::Min(a, b) |
+            Self::Max(a, b) => { a.names(out); b.names(out); },
+        }
+    }
+
+    /// Evaluate for the derived stat `id`, looking names up with `env`.
+    fn eval<F: Fn(&str) -> Option<i32>>(&self, id: &str, env: &F) -> Result<i32, FormulaError> {
+        let overflow = || FormulaError::Overflow(id.into());
+        Ok(match self {
+            Self::Num(n) => *n,
+            Self::Name(n) => env(n).ok_or_else(|| FormulaError::UnknownName { id: id.into(), name: n.clone() })?,
+            Self::Neg(e) => e.eval(id, env)?.checked_neg().ok_or_else(overflow)?,
+            Self::Bin(op, a, b) => {
+                let (a, b) = (a.eval(id, env)?, b.eval(id, env)?);
+                match op {
+                    Op::Add => a.checked_add(b).ok_or_else(overflow)?,
+                    Op::Sub => a.checked_sub(b).ok_or_else(overflow)?,
+                    Op::Mul => a.checked_mul(b).ok_or_else(overflow)?,
+                    Op::Div if b == 0 => return Err(FormulaError::DivisionByZero(id.into())),
+                    // i32::MIN / -1…
+                    Op::Div => a.checked_div(b).ok_or_else(overflow)?,
+                }
+            },
+            Self::Min(a, b) => a.eval(id, env)?.min(b.eval(id, env)?),
+            Self::Max(a, b) => a.eval(id, env)?.max(b.eval(id, env)?),
+        })
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Bin(op, _, _) => op.precedence(),
+            _ => 3
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Num(n) => write!(f, "{n}"),
+            Self::Name(n) => write!(f, "{n}"),
+            Self::Neg(e) if e.precedence() < 3 => write!(f, "-({e})"),
+            Self::Neg(e) => write!(f, "-{e}"),
+            Self::Bin(op, a, b) => {
+                let p = op.precedence();
+                if a.precedence() < p { write!(f, "({a})")?; } else { write!(f, "{a}")?; }
+                write!(f, " {op} ")?;
+                // right side needs parens on equal precedence too, e.g. a - (b - c)
+                if b.precedence() <= p && b.precedence() < 3 { write!(f, "({b})") } else { write!(f, "{b}") }
+            },
+            Self::Min(a, b) => write!(f, "min({a}, {b})"),
+            Self::Max(a, b) => write!(f, "max({a}, {b})"),
+        }
+    }
+}
+
+/// A (tiny) recursive descent parser for formulas.
+struct Parser<'a> {
+    src: &'a str,
+    at: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn err<T>(&self, msg: &str) -> Result<T, FormulaError> {
+        Err(FormulaError::Parse(format!("{msg} at {} in '{}'", self.at, self.src)))
+    }
+
+    fn skip_ws(&mut self) {
+        while let Some(c) = self.src[self.at..].chars().next() && c.is_whitespace() {
+            self.at += c.len_utf8();
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_ws();
+        self.src[self.at..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.at += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expr(&mut self, min_prec: u8) -> Result<Expr, FormulaError> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some('+') => Op::Add,
+                Some('-') => Op::Sub,
+                Some('*') => Op::Mul,
+                Some('/') => Op::Div,
+                _ => break
+            };
+            if op.precedence() < min_prec {
+                break
+            }
+            self.at += 1;
+            let rhs = self.expr(op.precedence() + 1)?;
+            lhs = Expr::Bin(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, FormulaError> {
+        if self.eat('-') {
+            return Ok(Expr::Neg(Box::new(self.unary()?)))
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Expr, FormulaError> {
+        match self.peek() {
+            Some('(') => {
+                self.at += 1;
+                let e = self.expr(1)?;
+                if !self.eat(')') {
+                    return self.err("expected ')'")
+                }
+                Ok(e)
+            },
+            Some(c) if c.is_ascii_digit() => {
+                let len = self.src[self.at..].find(|c: char| !c.is_ascii_digit()).unwrap_or(self.src.len() - self.at);
+                let n = self.src[self.at..self.at + len].parse().or_else(|_| self.err("number too big"))?;
+                self.at += len;
+                Ok(Expr::Num(n))
+            },
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let len = self.src[self.at..].find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(self.src.len() - self.at);
+                let name = self.src[self.at..self.at + len].to_string();
+                self.at += len;
+                match name.as_str() {
+                    "min" | "max" => {
+                        if !self.eat('(') { return self.err("expected '('") }
+                        let a = self.expr(1)?;
+                        if !self.eat(',') { return self.err("expected ','") }
+                        let b = self.expr(1)?;
+                        if !self.eat(')') { return self.err("expected ')'") }
+                        Ok(if name == "min" { Expr::Min(Box::new(a), Box::new(b)) } else { Expr::Max(Box::new(a), Box::new(b)) })
+                    },
+                    _ => Ok(Expr::Name(name))
+                }
+            },
+            Some(_) => self.err("unexpected character"),
+            None => self.err("unexpected end"),
+        }
+    }
+}
+
+/// A parsed formula.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Formula {
+    expr: Expr,
+}
+
+impl Formula {
+    /// Get the syntax tree.
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    /// Get the names (stat and derived stat ids) the formula refers to.
+    pub fn names(&self) -> Vec<&str> {
+        let mut out = vec![];
+        self.expr.names(&mut out);
+        out
+    }
+
+    /// Evaluate the formula, looking names up with `env`.
+    ///
+    /// # Returns
+    /// `None` if a name isn't found, on division by zero, or on overflow.
+    pub fn eval<F: Fn(&str) -> Option<i32>>(&self, env: F) -> Option<i32> {
+        self.expr.eval("", &env).ok()
+    }
+}
+
+impl FromStr for Formula {
+    type Err = FormulaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut p = Parser { src: s, at: 0 };
+        let expr = p.expr(1)?;
+        if p.peek().is_some() {
+            return p.err("unexpected trailing input")
+        }
+        Ok(Self { expr })
+    }
+}
+
+impl Display for Formula {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.expr)
+    }
+}
+
+impl Serialize for Formula {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Formula {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A single derived stat.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Derivation {
+    pub id: String,
+    pub name: String,
+    pub formula: Formula,
+}
+
+impl IsNamed for Derivation {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// A validated set of [Derivation]s, kept in dependency order.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Derivations {
+    defs: Vec<Derivation>,
+}
+
+impl Derivations {
+    /// Validate `defs`: unique ids, known names, no cycles.
+    pub fn new(defs: Vec<Derivation>) -> Result<Self, FormulaError> {
+        let ids: Vec<&str> = defs.iter().map(|d| d.id.as_str()).collect();
+        for (i, id) in ids.iter().enumerate() {
+            if ids[..i].contains(id) || StatBase::from_id(id).is_some() {
+                return Err(FormulaError::DuplicateId(id.to_string()))
+            }
+        }
+        for d in &defs {
+            if let Some(name) = d.formula.names().into_iter().find(|n| StatBase::from_id(n).is_none() && !ids.contains(n)) {
+                return Err(FormulaError::UnknownName { id: d.id.clone(), name: name.into() })
+            }
+        }
+
+        // depth-first topological sort; 'visiting' catches cycles
+        fn visit(i: usize, defs: &[Derivation], state: &mut [u8], order: &mut Vec<usize>) -> Result<(), FormulaError> {
+            match state[i] {
+                2 => return Ok(()),
+                1 => return Err(FormulaError::Cycle(defs[i].id.clone())),
+                _ => ()
+            }
+            state[i] = 1;
+            for name in defs[i].formula.names() {
+                if let Some(j) = defs.iter().position(|d| d.id == name) {
+                    visit(j, defs, state, order)?;
+                }
+            }
+            state[i] = 2;
+            order.push(i);
+            Ok(())
+        }
+        let mut state = vec![0; defs.len()];
+        let mut order = vec![];
+        for i in 0..defs.len() {
+            visit(i, &defs, &mut state, &mut order)?;
+        }
+        let mut defs: Vec<Option<Derivation>> = defs.into_iter().map(Some).collect();
+        Ok(Self { defs: order.into_iter().map(|i| defs[i].take().unwrap()).collect() })
+    }
+
+    /// Load derivations from JSON (an array of them).
+    pub fn from_json(json: &str) -> Result<Self, FormulaError> {
+        Self::new(serde_json::from_str(json)?)
+    }
+
+    /// Load derivations from a JSON file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, FormulaError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Get derivation by `id`.
+    pub fn get(&self, id: &str) -> Option<&Derivation> {
+        self.defs.iter().find(|d| d.id == id)
+    }
+
+    /// Iterate through the derivations in dependency order (dependencies first).
+    pub fn iter(&self) -> impl Iterator<Item = &Derivation> {
+        self.defs.iter()
+    }
+
+    /// Get ids of derivations depending (directly or not) on `name`, in dependency order.
+    pub fn dependents_of(&self, name: &str) -> Vec<&str> {
+        let mut affected: Vec<&str> = vec![];
+        for d in &self.defs {
+            if d.formula.names().iter().any(|n| *n == name || affected.contains(n)) {
+                affected.push(&d.id);
+            }
+        }
+        affected
+    }
+}
+
+impl<'de> Deserialize<'de> for Derivations {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(Vec::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A [StatBlock] with derived stats kept up to date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatSheet {
+    block: StatBlock,
+    derivations: Derivations,
+    values: BTreeMap<String, i32>,
+}
+
+impl StatSheet {
+    /// Make a sheet and compute all the derived values.
+    pub fn new(block: StatBlock, derivations: Derivations) -> Result<Self, FormulaError> {
+        let mut sheet = Self { block, derivations, values: BTreeMap::new() };
+        let all: Vec<String> = sheet.derivations.iter().map(|d| d.id.clone()).collect();
+        sheet.recompute(&all)?;
+        Ok(sheet)
+    }
+
+    /// (Re)compute the given derived values, in the given order.
+    fn recompute(&mut self, ids: &[String]) -> Result<(), FormulaError> {
+        for id in ids {
+            let d = self.derivations.get(id).unwrap();
+            let value = d.formula.expr.eval(id, &|n: &str| self.value(n))?;
+            self.values.insert(id.clone(), value);
+        }
+        Ok(())
+    }
+
+    /// Get the stats.
+    pub fn block(&self) -> &StatBlock {
+        &self.block
+    }
+
+    /// Get the derivations.
+    pub fn derivations(&self) -> &Derivations {
+        &self.derivations
+    }
+
+    /// Get a derived value by `id`.
+    pub fn derived(&self, id: &str) -> Option<i32> {
+        self.values.get(id).copied()
+    }
+
+    /// Get a stat or derived value by `id`.
+    pub fn value(&self, id: &str) -> Option<i32> {
+        match StatBase::from_id(id) {
+            Some(base) => Some(self.block.value(base)),
+            None => self.derived(id)
+        }
+    }
+
+    /// Replace a stat, and recompute whatever depends on it.
+    ///
+    /// On error the sheet is left as it was.
+    pub fn set(&mut self, stat: Stat) -> Result<(), FormulaError> {
+        let base = stat.stat_base();
+        let previous = self.block.get(base).clone();
+        self.block.set(stat);
+        self.changed(previous)
+    }
+
+    /// Modify a stat in place, e.g. `update(StatBase::Con, |con| *con += 2)`,
+    /// and recompute whatever depends on it.
+    ///
+    /// On error the sheet is left as it was.
+    pub fn update<F: FnOnce(&mut Stat)>(&mut self, base: StatBase, f: F) -> Result<(), FormulaError> {
+        let previous = self.block.get(base).clone();
+        self.block.update(base, f);
+        self.changed(previous)
+    }
+
+    /// Recompute whatever depends on the stat that used to be `previous`, or roll back to it.
+    fn changed(&mut self, previous: Stat) -> Result<(), FormulaError> {
+        let affected: Vec<String> = self.derivations.dependents_of(previous.stat_base().id()).into_iter().map(String::from).collect();
+        let values = self.values.clone();
+        self.recompute(&affected).inspect_err(|_| {
+            self.block.set(previous);
+            self.values = values;
+        })
+    }
+}
+
+#[cfg(test)]
+mod derived_tests {
+    use super::*;
+
+    fn f(s: &str) -> Formula {
+        s.parse().unwrap()
+    }
+
+    fn d(id: &str, formula: &str) -> Derivation {
+        Derivation { id: id.into(), name: id.into(), formula: f(formula) }
+    }
+
+    #[test]
+    fn parse_and_eval() {
+        let env = |n: &str| match n { "con" => Some(12), "str" => Some(9), _ => None };
+        assert_eq!(Some(28), f("con*2 + str/2").eval(env));
+        assert_eq!(Some(-3), f("-(str - 6)").eval(env));
+        assert_eq!(Some(2), f("12 - 4 * 2 - (1 + 1)").eval(env));
+        assert_eq!(Some(9), f("min(con, str)").eval(env));
+        assert_eq!(None, f("con / (str - 9)").eval(env));
+        assert_eq!(None, f("str * 1000000000").eval(env));
+        assert_eq!(None, f("-(0 - 2147483647 - 1)").eval(env));
+        assert!("con *".parse::<Formula>().is_err());
+        assert!("con + (str".parse::<Formula>().is_err());
+        assert!("max(1 2)".parse::<Formula>().is_err());
+    }
+
+    #[test]
+    fn display_round_trip() {
+        for s in ["con * 2 + str / 2", "a - (b - c)", "(a + b) * c", "-(a + 1)", "max(a, b * 2)"] {
+            assert_eq!(s, f(s).to_string());
+            assert_eq!(f(s), f(&f(s).to_string()));
+        }
+    }
+
+    #[test]
+    fn dependency_order_and_cycles() {
+        let ds = Derivations::new(vec![d("wounds", "hp / 2"), d("hp", "con * 2")]).unwrap();
+        assert_eq!(vec!["hp", "wounds"], ds.iter().map(|d| d.id.as_str()).collect::<Vec<_>>());
+        assert_eq!(vec!["hp", "wounds"], ds.dependents_of("con"));
+        assert!(matches!(Derivations::new(vec![d("a", "b + 1"), d("b", "a")]), Err(FormulaError::Cycle(_))));
+        assert!(matches!(Derivations::new(vec![d("a", "a")]), Err(FormulaError::Cycle(_))));
+        assert!(matches!(Derivations::new(vec![d("a", "luck")]), Err(FormulaError::UnknownName { .. })));
+        assert!(matches!(Derivations::new(vec![d("str", "1")]), Err(FormulaError::DuplicateId(_))));
+    }
+
+    #[test]
+    fn sheet_recomputes() {
+        let ds = Derivations::new(vec![d("hp", "con * 2 + str / 2"), d("carry", "str * 5"), d("bloodied", "hp / 2")]).unwrap();
+        let mut sheet = StatSheet::new(StatBlock::new(), ds).unwrap();
+        assert_eq!(Some(25), sheet.derived("hp"));
+        assert_eq!(Some(12), sheet.derived("bloodied"));
+        sheet.set(Stat::Str { val: 14 }).unwrap();
+        assert_eq!(Some(27), sheet.derived("hp"));
+        assert_eq!(Some(70), sheet.derived("carry"));
+        sheet.update(StatBase::Con, |c| *c -= 4).unwrap();
+        assert_eq!(Some(19), sheet.derived("hp"));
+        assert_eq!(Some(9), sheet.derived("bloodied"));
+    }
+
+    #[test]
+    fn evaluation_errors() {
+        let huge = Derivations::new(vec![d("load", "str * 1000000000")]).unwrap();
+        assert!(matches!(StatSheet::new(StatBlock::new(), huge), Err(FormulaError::Overflow(id)) if id == "load"));
+        let zero = Derivations::new(vec![d("ratio", "con / (str - 10)")]).unwrap();
+        assert!(matches!(StatSheet::new(StatBlock::new(), zero), Err(FormulaError::DivisionByZero(id)) if id == "ratio"));
+    }
+
+    #[test]
+    fn errors_roll_back() {
+        let ds = Derivations::new(vec![d("hp", "con * 2"), d("ratio", "hp / (str - 20)")]).unwrap();
+        let mut sheet = StatSheet::new(StatBlock::new(), ds).unwrap();
+        let before = sheet.clone();
+        assert!(matches!(sheet.set(Stat::Str { val: 20 }), Err(FormulaError::DivisionByZero(_))));
+        assert_eq!(before, sheet);
+        let ds = Derivations::new(vec![d("hp", "con * 2"), d("load", "hp * 100000000")]).unwrap();
+        let mut sheet = StatSheet::new(StatBlock::new(), ds).unwrap();
+        let before = sheet.clone();
+        assert!(matches!(sheet.update(StatBase::Con, |c| *c += 5), Err(FormulaError::Overflow(id)) if id == "load"));
+        assert_eq!(before, sheet);
+        assert_eq!(Some(10), sheet.value("con"));
+        assert_eq!(Some(20), sheet.derived("hp"));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let ds = Derivations::from_json(r#"[{"id": "hp", "name": "Hit Points", "formula": "con*2+str/2"}]"#).unwrap();
+        let json = serde_json::to_string(&ds).unwrap();
+        assert!(json.contains("\"con * 2 + str / 2\""));
+        assert_eq!(ds, serde_json::from_str(&json).unwrap());
+        assert!(serde_json::from_str::<Derivations>(r#"[{"id": "x", "name": "X", "formula": "x"}]"#).is_err());
+    }
+}
@@ -0,0 +1,340 @@
+//! Stat generation - filling a [StatBlock] by rolling, point-buy or standard arrays.
+//!
+//! All the generators implement [StatGenerator] and only touch the stats they're
+//! told to; everything else keeps its [default][StatBase::default]. Per-stat
+//! ranges of the [active rules][crate::stat::rules::RuleSet::active] are
+//! respected: rolls get clamped, while point-buy and arrays refuse to go
+//! out of them.
+//!
+//! ```
+//! use rpgassist::{rng::SeededRng, stat::{StatBase, generate::{Rolled, StatGenerator}}};
+//!
+//! let r#gen = Rolled::in_order("4d6kh3".parse().unwrap(), vec![StatBase::Str, StatBase::Dex, StatBase::Con]);
+//! let block = r#gen.generate_with(&mut SeededRng::new(867)).unwrap();
+//! assert!((3..=18).contains(&block.value(StatBase::Str)));
+//! ```
+use std::{collections::BTreeMap, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{audit::RollRecord, dice::{DiceExpr, DiceRoll}, rng::{ChaosRng, DiceRng}, stat::{StatBase, StatBlock, rules::RuleSet}};
+
+/// Stats typically generated (the rest being ±0 kind of things, or age).
+pub const GENERATED: [StatBase; 4] = [StatBase::Con, StatBase::Dex, StatBase::Int, StatBase::Str];
+
+/// Stat generation errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenerateError {
+    /// Number of values doesn't match the number of stats.
+    CountMismatch { stats: usize, values: usize },
+    /// The same stat was given more than once.
+    Duplicate(StatBase),
+    /// A value below the stat's minimum.
+    BelowMinimum { stat: StatBase, value: i32, min: i32 },
+    /// A value above the stat's maximum.
+    AboveMaximum { stat: StatBase, value: i32, max: i32 },
+    /// A value not found on the point-buy cost table.
+    NoCost { stat: StatBase, value: i32 },
+    /// Point-buy budget exceeded.
+    OverBudget { budget: i32, spent: i32 },
+    /// Point-buy costs or budget too big to add up.
+    Overflow,
+}
+
+impl Display for GenerateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CountMismatch { stats, values } => write!(f, "{values} value(s) for {stats} stat(s)"),
+            Self::Duplicate(stat) => write!(f, "{stat} given more than once"),
+            Self::BelowMinimum { stat, value, min } => write!(f, "{stat} {value} is below the minimum of {min}"),
+            Self::AboveMaximum { stat, value, max } => write!(f, "{stat} {value} is above the maximum of {max}"),
+            Self::NoCost { stat, value } => write!(f, "{stat} {value} isn't on the cost table"),
+            Self::OverBudget { budget, spent } => write!(f, "spent {spent} points out of {budget}"),
+            Self::Overflow => write!(f, "point-buy costs don't fit in an i32"),
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {}
+
+/// Check that none of the `stats` is there twice.
+fn check_duplicates(stats: &[StatBase]) -> Result<(), GenerateError> {
+    for (i, s) in stats.iter().enumerate() {
+        if stats[..i].contains(s) {
+            return Err(GenerateError::Duplicate(*s))
+        }
+    }
+    Ok(())
+}
+
+/// Check `value` against the `stat`'s range in the active rules.
+fn check_range(stat: StatBase, value: i32) -> Result<(), GenerateError> {
+    let range = RuleSet::active().range(stat);
+    match (range.min, range.max) {
+        (Some(min), _) if value < min => Err(GenerateError::BelowMinimum { stat, value, min }),
+        (_, Some(max)) if value > max => Err(GenerateError::AboveMaximum { stat, value, max }),
+        _ => Ok(())
+    }
+}
+
+/// Anything that fills a [StatBlock].
+pub trait StatGenerator {
+    /// Generate a stat block.
+    fn generate(&self) -> Result<StatBlock, GenerateError> {
+        self.generate_with(&mut ChaosRng)
+    }
+
+    /// Generate a stat block using the given `rng` (if randomness is involved at all).
+    fn generate_with<R: DiceRng + ?Sized>(&self, rng: &mut R) -> Result<StatBlock, GenerateError>;
+}
+
+/// Rolled stats, e.g. `4d6kh3` a piece.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Rolled {
+    pub dice: DiceExpr,
+    /// Stats to roll for - in order, or in priority order if `arrange`d.
+    pub stats: Vec<StatBase>,
+    /// Sort the rolls, highest going to the first of `stats`.
+    #[serde(default)]
+    pub arrange: bool,
+}
+
+impl Rolled {
+    /// Roll `dice` for each of the `stats`, in order.
+    pub fn in_order(dice: DiceExpr, stats: Vec<StatBase>) -> Self {
+        Self { dice, stats, arrange: false }
+    }
+
+    /// Roll `dice` as many times as there's `stats`, and assign the highest roll
+    /// to the first stat, the second highest to the second one, etc.
+    pub fn arranged(dice: DiceExpr, priority: Vec<StatBase>) -> Self {
+        Self { dice, stats: priority, arrange: true }
+    }
+}
+
+impl Default for Rolled {
+    /// `4d6kh3` in order for the [usual suspects][GENERATED].
+    fn default() -> Self {
+        Self::in_order("4d6kh3".parse().unwrap(), GENERATED.to_vec())
+    }
+}
+
+impl StatGenerator for Rolled {
+    fn generate_with<R: DiceRng + ?Sized>(&self, rng: &mut R) -> Result<StatBlock, GenerateError> {
+        check_duplicates(&self.stats)?;
+        let mut rolls: Vec<DiceRoll> = self.stats.iter().map(|_| self.dice.roll_with(rng)).collect();
+        if self.arrange {
+            rolls.sort_by_key(|r| std::cmp::Reverse(r.total()));
+        }
+        let mut block = StatBlock::new();
+        for (stat, roll) in self.stats.iter().zip(rolls) {
+            let val = stat.clamp_value(roll.total());
+            if rng.is_recording() {
+                rng.record(RollRecord::new("Rolled::generate", &self.dice, roll.raw(), stat.with(val)));
+            }
+            block.set(stat.with(val));
+        }
+        Ok(block)
+    }
+}
+
+/// Point-buy - stats bought for points out of a budget.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct PointBuy {
+    /// Stat value → (total) cost of buying it.
+    pub costs: BTreeMap<i32, i32>,
+    pub budget: i32,
+    /// The values bought.
+    #[serde(default)]
+    pub values: BTreeMap<StatBase, i32>,
+}
+
+impl PointBuy {
+    /// A point-buy with the given `costs` (value → total cost) and `budget`.
+    pub fn new(costs: BTreeMap<i32, i32>, budget: i32) -> Self {
+        Self { costs, budget, values: BTreeMap::new() }
+    }
+
+    /// Buy `value` for the `stat`.
+    pub fn with(mut self, stat: StatBase, value: i32) -> Self {
+        self.values.insert(stat, value);
+        self
+    }
+
+    /// Get the cost of `value`, if on the cost table.
+    pub fn cost(&self, value: i32) -> Option<i32> {
+        self.costs.get(&value).copied()
+    }
+
+    /// Total points spent so far.
+    pub fn spent(&self) -> Result<i32, GenerateError> {
+        self.values.iter().try_fold(0i32, |spent, (stat, value)| {
+            let cost = self.cost(*value).ok_or(GenerateError::NoCost { stat: *stat, value: *value })?;
+            spent.checked_add(cost).ok_or(GenerateError::Overflow)
+        })
+    }
+
+    /// Points left in the budget (negative if overspent).
+    pub fn remaining(&self) -> Result<i32, GenerateError> {
+        self.budget.checked_sub(self.spent()?).ok_or(GenerateError::Overflow)
+    }
+
+    /// Validate the purchases: values on the cost table, within stat ranges, and within the budget.
+    pub fn validate(&self) -> Result<(), GenerateError> {
+        for (stat, value) in &self.values {
+            check_range(*stat, *value)?;
+        }
+        let spent = self.spent()?;
+        if spent > self.budget {
+            return Err(GenerateError::OverBudget { budget: self.budget, spent })
+        }
+        Ok(())
+    }
+}
+
+impl Default for PointBuy {
+    /// Values 8 to 15 (costs 0–9, getting steeper at the top end) with a budget of 27.
+    fn default() -> Self {
+        Self::new(BTreeMap::from([(8, 0), (9, 1), (10, 2), (11, 3), (12, 4), (13, 5), (14, 7), (15, 9)]), 27)
+    }
+}
+
+impl StatGenerator for PointBuy {
+    /// Point-buy involves no dice; `rng` is ignored.
+    fn generate_with<R: DiceRng + ?Sized>(&self, _rng: &mut R) -> Result<StatBlock, GenerateError> {
+        self.validate()?;
+        Ok(self.values.iter().map(|(stat, value)| stat.with(*value)).collect())
+    }
+}
+
+/// A fixed array of values, assigned to stats in the given order.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct StandardArray {
+    pub values: Vec<i32>,
+    pub stats: Vec<StatBase>,
+}
+
+impl StandardArray {
+    /// Assign `values` to `stats`, in order.
+    pub fn new(values: Vec<i32>, stats: Vec<StatBase>) -> Self {
+        Self { values, stats }
+    }
+
+    /// Assign `values` to the `priority` stats highest first.
+    pub fn arranged(mut values: Vec<i32>, priority: Vec<StatBase>) -> Self {
+        values.sort_by(|a, b| b.cmp(a));
+        Self::new(values, priority)
+    }
+}
+
+impl Default for StandardArray {
+    /// `13, 12, 11, 10` for the [usual suspects][GENERATED].
+    fn default() -> Self {
+        Self::new(vec![13, 12, 11, 10], GENERATED.to_vec())
+    }
+}
+
+impl StatGenerator for StandardArray {
+    /// Arrays involve no dice; `rng` is ignored.
+    fn generate_with<R: DiceRng + ?Sized>(&self, _rng: &mut R) -> Result<StatBlock, GenerateError> {
+        if self.values.len() != self.stats.len() {
+            return Err(GenerateError::CountMismatch { stats: self.stats.len(), values: self.values.len() })
+        }
+        check_duplicates(&self.stats)?;
+        for (stat, value) in self.stats.iter().zip(&self.values) {
+            check_range(*stat, *value)?;
+        }
+        Ok(self.stats.iter().zip(&self.values).map(|(stat, value)| stat.with(*value)).collect())
+    }
+}
+
+#[cfg(test)]
+mod generate_tests {
+    use crate::{audit::AuditedRng, rng::SeededRng};
+
+    use super::*;
+
+    #[test]
+    fn rolled_in_order_stays_in_range() {
+        let r#gen = Rolled::default();
+        let mut rng = SeededRng::new(16);
+        for _ in 0..50 {
+            let b = r#gen.generate_with(&mut rng).unwrap();
+            for s in GENERATED {
+                assert!((3..=18).contains(&b.value(s)));
+            }
+            assert_eq!(18, b.value(StatBase::Age));
+        }
+    }
+
+    #[test]
+    fn rolled_arranged_is_sorted_by_priority() {
+        let prio = vec![StatBase::Str, StatBase::Con, StatBase::Dex, StatBase::Int];
+        let r#gen = Rolled::arranged("3d6".parse().unwrap(), prio.clone());
+        let mut rng = AuditedRng::new(SeededRng::new(17));
+        let b = r#gen.generate_with(&mut rng).unwrap();
+        assert!(prio.windows(2).all(|w| b.value(w[0]) >= b.value(w[1])));
+        assert_eq!(4, rng.records().iter().filter(|r| r.source == "Rolled::generate").count());
+        assert!(rng.records().iter().all(|r| r.raw.len() == 3 && r.raw.iter().all(|d| (1..=6).contains(d))));
+    }
+
+    #[test]
+    fn rolls_respect_minimums() {
+        let r#gen = Rolled::in_order("1d2-10".parse().unwrap(), vec![StatBase::Str, StatBase::Int, StatBase::Cha]);
+        let b = r#gen.generate_with(&mut SeededRng::new(18)).unwrap();
+        assert_eq!(1, b.value(StatBase::Str));
+        assert_eq!(0, b.value(StatBase::Int));
+        assert!(b.value(StatBase::Cha) < 0);
+    }
+
+    #[test]
+    fn point_buy_budget() {
+        let pb = PointBuy::default()
+            .with(StatBase::Str, 15)
+            .with(StatBase::Dex, 14)
+            .with(StatBase::Con, 13);
+        assert_eq!(Ok(6), pb.remaining());
+        let b = pb.generate().unwrap();
+        assert_eq!(15, b.value(StatBase::Str));
+        let pb = pb.with(StatBase::Int, 15).with(StatBase::Will, 15);
+        assert_eq!(Err(GenerateError::OverBudget { budget: 27, spent: 39 }), pb.validate());
+        let pb = PointBuy::default().with(StatBase::Int, 18);
+        assert_eq!(Err(GenerateError::NoCost { stat: StatBase::Int, value: 18 }), pb.validate());
+    }
+
+    #[test]
+    fn point_buy_minimums() {
+        let pb = PointBuy::new(BTreeMap::from([(0, 0), (1, 1)]), 10).with(StatBase::Dex, 0);
+        assert_eq!(Err(GenerateError::BelowMinimum { stat: StatBase::Dex, value: 0, min: 1 }), pb.validate());
+    }
+
+    #[test]
+    fn point_buy_overflow() {
+        let pb = PointBuy::new(BTreeMap::from([(10, i32::MAX)]), 27).with(StatBase::Str, 10).with(StatBase::Dex, 10);
+        assert_eq!(Err(GenerateError::Overflow), pb.validate());
+        let pb = PointBuy::new(BTreeMap::from([(10, 1)]), i32::MIN).with(StatBase::Str, 10);
+        assert_eq!(Err(GenerateError::Overflow), pb.remaining());
+    }
+
+    #[test]
+    fn active_rules_apply() {
+        use crate::stat::rules::StatRange;
+        let _g = RuleSet::classic().with(StatBase::Str, StatRange::new(Some(3), Some(12))).activate();
+        let b = Rolled::in_order("1d6+20".parse().unwrap(), vec![StatBase::Str]).generate().unwrap();
+        assert_eq!(12, b.value(StatBase::Str));
+        let pb = PointBuy::default().with(StatBase::Str, 14);
+        assert_eq!(Err(GenerateError::AboveMaximum { stat: StatBase::Str, value: 14, max: 12 }), pb.validate());
+    }
+
+    #[test]
+    fn standard_arrays() {
+        let b = StandardArray::default().generate().unwrap();
+        assert_eq!(13, b.value(StatBase::Con));
+        assert_eq!(10, b.value(StatBase::Str));
+        let b = StandardArray::arranged(vec![8, 15, 12], vec![StatBase::Int, StatBase::Will, StatBase::Str]).generate().unwrap();
+        assert_eq!(15, b.value(StatBase::Int));
+        assert_eq!(8, b.value(StatBase::Str));
+        assert!(matches!(StandardArray::new(vec![1], vec![]).generate(), Err(GenerateError::CountMismatch { .. })));
+        assert!(matches!(StandardArray::new(vec![1, 2], vec![StatBase::Str, StatBase::Str]).generate(), Err(GenerateError::Duplicate(_))));
+    }
+}
@@ -0,0 +1,365 @@
+//! Data-driven stat definitions - for stats beyond the built-in nine [StatBase][crate::stat::StatBase]s,
+//! e.g. Perception, Luck, Sanity or Edge.
+//!
+//! The built-in nine ship as [StatRegistry::builtin] (bundled `data/stats/builtin.json`);
+//! system specific ones get [added][StatRegistry::add] or loaded from JSON:
+//!
+//! ```
+//! use rpgassist::stat::registry::{StatRegistry, StatValues};
+//!
+//! let mut reg = StatRegistry::builtin().clone();
+//! reg.merge(StatRegistry::from_json(r#"[
+//!     { "id": "san", "name": "Sanity", "label": "SAN", "default": 50, "min": 0, "max": 99 }
+//! ]"#).unwrap()).unwrap();
+//! let mut values = StatValues::new();
+//! assert_eq!(Ok(50), values.value(&reg, "san"));
+//! assert_eq!(Ok(99), values.set(&reg, "san", 120));// clamped
+//! assert_eq!(Ok(10), values.value(&reg, "str"));
+//! ```
+use std::{collections::BTreeMap, fmt::Display, path::Path, sync::OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ext::IsNamed, stat::StatBlock};
+
+/// What happens to values out of a stat's bounds.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ClampRule {
+    /// Silently bring the value within bounds.
+    #[default]
+    Clamp,
+    /// Refuse the value with [StatDefError::OutOfRange].
+    Reject,
+}
+
+/// Something wrong with stat definitions (or loading them), or values.
+#[derive(Debug)]
+pub enum StatDefError {
+    /// Reading the definition data failed.
+    Io(std::io::Error),
+    /// JSON didn't parse as definitions.
+    Json(serde_json::Error),
+    /// Two or more definitions with the same id.
+    DuplicateId(String),
+    /// No such stat defined.
+    UnknownStat(String),
+    /// Min above max, or default out of bounds.
+    BadBounds(String),
+    /// Value out of bounds (with [ClampRule::Reject]).
+    OutOfRange { id: String, value: i32 },
+}
+
+impl Display for StatDefError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Cannot read stat definitions: {e}"),
+            Self::Json(e) => write!(f, "Cannot parse stat definitions: {e}"),
+            Self::DuplicateId(id) => write!(f, "Stat '{id}' defined more than once"),
+            Self::UnknownStat(id) => write!(f, "No stat '{id}' defined"),
+            Self::BadBounds(id) => write!(f, "Stat '{id}' has inconsistent min/max/default"),
+            Self::OutOfRange { id, value } => write!(f, "Value {value} is out of bounds for stat '{id}'"),
+        }
+    }
+}
+
+impl std::error::Error for StatDefError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Json(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for StatDefError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for StatDefError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+/// Compare [StatDefError]s - I/O and JSON errors by their kind/category only.
+impl PartialEq for StatDefError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Io(a), Self::Io(b)) => a.kind() == b.kind(),
+            (Self::Json(a), Self::Json(b)) => a.classify() == b.classify(),
+            (Self::DuplicateId(a), Self::DuplicateId(b)) |
+            (Self::UnknownStat(a), Self::UnknownStat(b)) |
+            (Self::BadBounds(a), Self::BadBounds(b)) => a == b,
+            (Self::OutOfRange { id: a, value: x }, Self::OutOfRange { id: b, value: y }) => a == b && x == y,
+            _ => false
+        }
+    }
+}
+
+/// Definition of a single stat.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct StatDef {
+    /// Unique id, e.g. `"str"`.
+    pub id: String,
+    /// Display name, e.g. `"Strength"`.
+    pub name: String,
+    /// Short label, e.g. `"STR"`.
+    pub label: String,
+    #[serde(default)]
+    pub default: i32,
+    #[serde(default)]
+    pub min: Option<i32>,
+    #[serde(default)]
+    pub max: Option<i32>,
+    #[serde(default)]
+    pub clamp: ClampRule,
+}
+
+impl StatDef {
+    /// A new, unbounded, stat definition.
+    pub fn new(id: &str, name: &str, label: &str, default: i32) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            label: label.into(),
+            default,
+            min: None,
+            max: None,
+            clamp: ClampRule::default(),
+        }
+    }
+
+    /// Set bounds.
+    pub fn bounded(mut self, min: Option<i32>, max: Option<i32>) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// Set clamping rule.
+    pub fn with_clamp(mut self, clamp: ClampRule) -> Self {
+        self.clamp = clamp;
+        self
+    }
+
+    /// Check that the bounds make sense and the default is within them.
+    pub fn validate(&self) -> Result<(), StatDefError> {
+        let bad = match (self.min, self.max) {
+            (Some(min), Some(max)) => min > max || !(min..=max).contains(&self.default),
+            (Some(min), None) => self.default < min,
+            (None, Some(max)) => self.default > max,
+            (None, None) => false
+        };
+        if bad {
+            return Err(StatDefError::BadBounds(self.id.clone()))
+        }
+        Ok(())
+    }
+
+    /// Apply bounds to `value`, as per the [clamping rule][ClampRule].
+    pub fn apply(&self, value: i32) -> Result<i32, StatDefError> {
+        let clamped = value
+            .max(self.min.unwrap_or(i32::MIN))
+            .min(self.max.unwrap_or(i32::MAX));
+        match self.clamp {
+            ClampRule::Reject if clamped != value => Err(StatDefError::OutOfRange { id: self.id.clone(), value }),
+            _ => Ok(clamped)
+        }
+    }
+}
+
+impl IsNamed for StatDef {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Display for StatDef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+/// A set of [StatDef]s, in definition order.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Default)]
+#[serde(transparent)]
+pub struct StatRegistry {
+    defs: Vec<StatDef>,
+}
+
+impl StatRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The built-in nine, matching [StatBase][crate::stat::StatBase].
+    pub fn builtin() -> &'static StatRegistry {
+        static BUILTIN: OnceLock<StatRegistry> = OnceLock::new();
+        BUILTIN.get_or_init(|| Self::from_json(include_str!("../../data/stats/builtin.json"))
+            .expect("Bundled stat definitions are broken?!"))
+    }
+
+    /// A registry of `defs`, each [added][Self::add] in turn.
+    pub fn from_defs(defs: impl IntoIterator<Item = StatDef>) -> Result<Self, StatDefError> {
+        let mut reg = Self::new();
+        for def in defs {
+            reg.add(def)?;
+        }
+        Ok(reg)
+    }
+
+    /// Load definitions from JSON (an array of them).
+    pub fn from_json(json: &str) -> Result<Self, StatDefError> {
+        let defs: Vec<StatDef> = serde_json::from_str(json)?;
+        Self::from_defs(defs)
+    }
+
+    /// Load definitions from a JSON file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, StatDefError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Add a definition.
+    pub fn add(&mut self, def: StatDef) -> Result<(), StatDefError> {
+        def.validate()?;
+        if self.get(&def.id).is_some() {
+            return Err(StatDefError::DuplicateId(def.id))
+        }
+        self.defs.push(def);
+        Ok(())
+    }
+
+    /// Add all the definitions from `other`.
+    pub fn merge(&mut self, other: StatRegistry) -> Result<(), StatDefError> {
+        other.defs.into_iter().try_for_each(|d| self.add(d))
+    }
+
+    /// Get definition by `id`.
+    pub fn get(&self, id: &str) -> Option<&StatDef> {
+        self.defs.iter().find(|d| d.id == id)
+    }
+
+    /// Get definition by `id`, or [StatDefError::UnknownStat].
+    fn expect(&self, id: &str) -> Result<&StatDef, StatDefError> {
+        self.get(id).ok_or_else(|| StatDefError::UnknownStat(id.into()))
+    }
+
+    /// Iterate through the definitions, in definition order.
+    pub fn iter(&self) -> impl Iterator<Item = &StatDef> {
+        self.defs.iter()
+    }
+}
+
+impl<'de> Deserialize<'de> for StatRegistry {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::from_defs(Vec::<StatDef>::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Stat values by stat id, checked against a [StatRegistry].
+///
+/// Stats without a value of their own fall back to their definition's default.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+#[serde(transparent)]
+pub struct StatValues {
+    values: BTreeMap<String, i32>,
+}
+
+impl StatValues {
+    /// No values (yet) - i.e. defaults all around.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get value of stat `id`.
+    pub fn value(&self, registry: &StatRegistry, id: &str) -> Result<i32, StatDefError> {
+        let def = registry.expect(id)?;
+        Ok(self.values.get(id).copied().unwrap_or(def.default))
+    }
+
+    /// Set value of stat `id`. Returns the value actually set (after clamping).
+    pub fn set(&mut self, registry: &StatRegistry, id: &str, value: i32) -> Result<i32, StatDefError> {
+        let value = registry.expect(id)?.apply(value)?;
+        self.values.insert(id.into(), value);
+        Ok(value)
+    }
+
+    /// Adjust value of stat `id` by `delta` (saturating at the `i32` limits). Returns the resulting value.
+    pub fn adjust(&mut self, registry: &StatRegistry, id: &str, delta: i32) -> Result<i32, StatDefError> {
+        let value = self.value(registry, id)?;
+        self.set(registry, id, value.saturating_add(delta))
+    }
+
+    /// Iterate through all the stats of the `registry` and their values, in definition order.
+    pub fn iter<'a>(&'a self, registry: &'a StatRegistry) -> impl Iterator<Item = (&'a StatDef, i32)> {
+        registry.iter().map(|d| (d, self.values.get(&d.id).copied().unwrap_or(d.default)))
+    }
+}
+
+impl From<&StatBlock> for StatValues {
+    /// Built-in stat values, by their [ids][crate::stat::StatBase::id].
+    fn from(block: &StatBlock) -> Self {
+        Self { values: block.iter().map(|s| (s.stat_base().id().to_string(), s.value())).collect() }
+    }
+}
+
+#[cfg(test)]
+mod registry_tests {
+    use crate::stat::{Stat, StatBase};
+
+    use super::*;
+
+    #[test]
+    fn builtin_matches_statbase() {
+        let reg = StatRegistry::builtin();
+        assert_eq!(9, reg.iter().count());
+        for base in StatBase::ALL {
+            let def = reg.get(base.id()).unwrap();
+            assert_eq!(base.default().value(), def.default);
+            assert_eq!(base.minimum(), def.min);
+            assert_eq!(base.to_string(), def.label);
+            assert_eq!(Some(base), StatBase::from_id(&def.id));
+        }
+    }
+
+    #[test]
+    fn custom_stats() {
+        let mut reg = StatRegistry::builtin().clone();
+        reg.add(StatDef::new("luck", "Luck", "LCK", 3).bounded(Some(0), Some(6)).with_clamp(ClampRule::Reject)).unwrap();
+        let mut v = StatValues::new();
+        assert_eq!(Ok(3), v.value(&reg, "luck"));
+        assert_eq!(Ok(5), v.adjust(&reg, "luck", 2));
+        assert_eq!(Err(StatDefError::OutOfRange { id: "luck".into(), value: 7 }), v.adjust(&reg, "luck", 2));
+        assert_eq!(Ok(5), v.value(&reg, "luck"));
+        assert_eq!(Err(StatDefError::UnknownStat("edge".into())), v.value(&reg, "edge"));
+        assert_eq!(Ok(1), v.set(&reg, "con", -5));
+        assert_eq!(Ok(i32::MAX), v.adjust(&reg, "dex", i32::MAX));
+        assert_eq!(Ok(1), v.adjust(&reg, "dex", i32::MIN));
+    }
+
+    #[test]
+    fn broken_definitions() {
+        assert_eq!(Err(StatDefError::DuplicateId("x".into())),
+            StatRegistry::from_json(r#"[{"id":"x","name":"X","label":"X"},{"id":"x","name":"Y","label":"Y"}]"#));
+        assert_eq!(Err(StatDefError::BadBounds("x".into())),
+            StatRegistry::from_json(r#"[{"id":"x","name":"X","label":"X","default":5,"max":3}]"#));
+        assert!(serde_json::from_str::<StatRegistry>(r#"[{"id":"x","name":"X","label":"X"},{"id":"x","name":"Y","label":"Y"}]"#).is_err());
+        assert!(serde_json::from_str::<StatRegistry>(r#"[{"id":"x","name":"X","label":"X","default":5,"max":3}]"#).is_err());
+        let reg: StatRegistry = serde_json::from_str(&serde_json::to_string(StatRegistry::builtin()).unwrap()).unwrap();
+        assert_eq!(StatRegistry::builtin(), &reg);
+    }
+
+    #[test]
+    fn from_stat_block() {
+        let mut b = StatBlock::new();
+        b.set(Stat::Str { val: 15 });
+        let v = StatValues::from(&b);
+        assert_eq!(Ok(15), v.value(StatRegistry::builtin(), "str"));
+        assert_eq!(9, v.iter(StatRegistry::builtin()).count());
+    }
+}
@@ -0,0 +1,434 @@
+//! Per game system stat rules - ranges, soft caps, racial maxima and the
+//! [modifier curves][ModifierCurve] of stats.
+//!
+//! All arithmetic on [Stat] goes through the *active* [RuleSet] of the current
+//! thread. By default that's [RuleSet::classic], i.e. the good old clamps:
+//! App/Cha unbounded, Age/Int/Mag/Will min 0 and Con/Dex/Str min 1.
+//! Another rule set can be activated for a scope:
+//!
+//! ```
+//! use rpgassist::stat::{Stat, StatBase, rules::{RuleSet, StatRange}};
+//!
+//! let rules = RuleSet::classic().with(StatBase::Str, StatRange::new(Some(3), Some(18)));
+//! {
+//!     let _guard = rules.activate();
+//!     assert_eq!(18, (Stat::Str { val: 10 } + 500).value());
+//! }
+//! // back to classic rules…
+//! assert_eq!(510, (Stat::Str { val: 10 } + 500).value());
+//! ```
+use std::{collections::BTreeMap, fmt::Display, num::NonZeroU32, path::Path, rc::Rc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{scoped::{self, ScopeGuard, Scoped}, stat::{Stat, StatBase, StatBlock}};
+
+/// Soft cap - gains above `at` only count every `divisor`th point.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct SoftCap {
+    pub at: i32,
+    pub divisor: NonZeroU32,
+}
+
+/// Allowed range of a stat.
+///
+/// Deserializing rejects ranges with `min` above `max`.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StatRange {
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+    pub soft_cap: Option<SoftCap>,
+}
+
+/// [StatRange] as is, before validation.
+#[derive(Deserialize)]
+struct RawStatRange {
+    #[serde(default)]
+    min: Option<i32>,
+    #[serde(default)]
+    max: Option<i32>,
+    #[serde(default)]
+    soft_cap: Option<SoftCap>,
+}
+
+impl<'de> Deserialize<'de> for StatRange {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let RawStatRange { min, max, soft_cap } = RawStatRange::deserialize(deserializer)?;
+        let range = Self { min, max, soft_cap };
+        range.check().map_err(serde::de::Error::custom)?;
+        Ok(range)
+    }
+}
+
+impl StatRange {
+    /// A range from `min` to `max` (either of them optional).
+    pub fn new(min: Option<i32>, max: Option<i32>) -> Self {
+        Self { min, max, soft_cap: None }
+    }
+
+    /// Set a [soft cap][SoftCap]. A `divisor` of zero counts as one.
+    pub fn with_soft_cap(mut self, at: i32, divisor: u32) -> Self {
+        self.soft_cap = Some(SoftCap { at, divisor: NonZeroU32::new(divisor).unwrap_or(NonZeroU32::MIN) });
+        self
+    }
+
+    /// Check that the range isn't inverted, i.e. `min` isn't above `max`.
+    pub fn check(&self) -> Result<(), RuleError> {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if min > max => Err(RuleError::InvertedRange { min, max }),
+            _ => Ok(())
+        }
+    }
+
+    /// Bring `val` within the range.
+    pub fn clamp(&self, val: i32) -> i32 {
+        val.max(self.min.unwrap_or(i32::MIN)).min(self.max.unwrap_or(i32::MAX))
+    }
+
+    /// Is `val` within the range?
+    pub fn contains(&self, val: i32) -> bool {
+        self.clamp(val) == val
+    }
+
+    /// Apply `delta` to `val`: soft cap dampens gains above it, and the result
+    /// gets [clamped][StatRange::clamp].
+    pub fn apply(&self, val: i32, delta: i32) -> i32 {
+        let raw = match self.soft_cap {
+            Some(cap) if delta > 0 && val.saturating_add(delta) > cap.at => {
+                let below = cap.at.saturating_sub(val).clamp(0, delta);
+                let divisor = i32::try_from(cap.divisor.get()).unwrap_or(i32::MAX);
+                (val + below).saturating_add((delta - below) / divisor)
+            },
+            _ => val.saturating_add(delta)
+        };
+        self.clamp(raw)
+    }
+}
+
+/// How a stat value translates to a [modifier][crate::modifier::HasModifier] for rolls.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModifierCurve {
+    /// No modifier at all.
+    None,
+    /// The value itself.
+    Linear,
+    /// Every `per` points above/below `base` count ±1, e.g. the d20 curve of
+    /// base 10, per 2.
+    Stepped { base: i32, per: NonZeroU32 },
+}
+
+impl ModifierCurve {
+    /// Get the modifier for `val`.
+    pub fn modifier(&self, val: i32) -> i32 {
+        match self {
+            Self::None => 0,
+            Self::Linear => val,
+            Self::Stepped { base, per } => (val as i64 - *base as i64)
+                .div_euclid(per.get() as i64)
+                .clamp(i32::MIN as i64, i32::MAX as i64) as i32,
+        }
+    }
+}
+
+/// A stat out of its allowed range (or failure to load a [RuleSet]).
+#[derive(Debug)]
+pub enum RuleError {
+    /// Reading the rule data failed.
+    Io(std::io::Error),
+    /// JSON didn't parse as rules.
+    Json(serde_json::Error),
+    /// Stat value out of its allowed range.
+    OutOfRange { stat: StatBase, value: i32, range: StatRange },
+    /// No such race in the rule set.
+    UnknownRace(String),
+    /// Range with its `min` above its `max`.
+    InvertedRange { min: i32, max: i32 },
+}
+
+impl Display for RuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Cannot read rule data: {e}"),
+            Self::Json(e) => write!(f, "Cannot parse rule data: {e}"),
+            Self::OutOfRange { stat, value, range } => {
+                write!(f, "{stat} {value} is out of range")?;
+                match (range.min, range.max) {
+                    (Some(min), Some(max)) => write!(f, " {min}–{max}"),
+                    (Some(min), None) => write!(f, " (min {min})"),
+                    (None, Some(max)) => write!(f, " (max {max})"),
+                    (None, None) => Ok(())
+                }
+            },
+            Self::UnknownRace(race) => write!(f, "No racial maxima for '{race}'"),
+            Self::InvertedRange { min, max } => write!(f, "Range min {min} is above its max {max}"),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Json(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for RuleError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for RuleError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+/// Stat rules of a game system.
+///
+/// Stats without a [range][StatRange] of their own are unbounded, and ones
+/// without a [modifier curve][ModifierCurve] use their [classic one][StatBase::curve].
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct RuleSet {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    stats: BTreeMap<StatBase, StatRange>,
+    /// Race → stat maxima.
+    #[serde(default)]
+    races: BTreeMap<String, BTreeMap<StatBase, i32>>,
+    /// Stat → modifier curve.
+    #[serde(default)]
+    curves: BTreeMap<StatBase, ModifierCurve>,
+}
+
+thread_local! {
+    static ACTIVE: Scoped<RuleSet> = Scoped::new(RuleSet::classic());
+}
+
+/// Deactivates an [activated][RuleSet::activate] [RuleSet] when dropped.
+pub type RuleSetGuard = ScopeGuard<RuleSet>;
+
+impl RuleSet {
+    /// A rule set with no rules at all, i.e. everything unbounded.
+    pub fn new(name: &str) -> Self {
+        Self { name: name.into(), ..Default::default() }
+    }
+
+    /// The classic rules, i.e. [StatBase::minimum]s, no maximums and the
+    /// [classic modifier curves][StatBase::curve].
+    pub fn classic() -> Self {
+        let mut rules = Self::new("classic");
+        for base in StatBase::ALL {
+            if let Some(min) = base.minimum() {
+                rules.stats.insert(base, StatRange::new(Some(min), None));
+            }
+            rules.curves.insert(base, base.curve());
+        }
+        rules
+    }
+
+    /// Load rules from JSON.
+    pub fn from_json(json: &str) -> Result<Self, RuleError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Load rules from a JSON file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, RuleError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Get the name of the rule set.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set the range of a stat.
+    pub fn with(mut self, stat: StatBase, range: StatRange) -> Self {
+        self.stats.insert(stat, range);
+        self
+    }
+
+    /// Set the modifier curve of a stat.
+    pub fn with_curve(mut self, stat: StatBase, curve: ModifierCurve) -> Self {
+        self.curves.insert(stat, curve);
+        self
+    }
+
+    /// Set racial maximum of a stat.
+    pub fn with_racial_max(mut self, race: &str, stat: StatBase, max: i32) -> Self {
+        self.races.entry(race.into()).or_default().insert(stat, max);
+        self
+    }
+
+    /// Get the range of a stat.
+    pub fn range(&self, stat: StatBase) -> StatRange {
+        self.stats.get(&stat).copied().unwrap_or_default()
+    }
+
+    /// Get the rules as they apply to the given `race`, i.e. with the racial
+    /// maxima folded into the ranges.
+    pub fn for_race(&self, race: &str) -> Result<Self, RuleError> {
+        let Some(maxima) = self.races.get(race) else {
+            return Err(RuleError::UnknownRace(race.into()))
+        };
+        let mut rules = self.clone();
+        rules.name = format!("{} ({race})", self.name);
+        for (stat, max) in maxima {
+            let range = rules.stats.entry(*stat).or_default();
+            range.max = Some(range.max.map_or(*max, |m| m.min(*max)));
+            range.check()?;
+        }
+        Ok(rules)
+    }
+
+    /// Get the modifier curve of a stat.
+    pub fn curve(&self, stat: StatBase) -> ModifierCurve {
+        self.curves.get(&stat).copied().unwrap_or_else(|| stat.curve())
+    }
+
+    /// Get the modifier a `stat` value of `val` gives, as per the rules.
+    pub fn modifier(&self, stat: StatBase, val: i32) -> i32 {
+        self.curve(stat).modifier(val)
+    }
+
+    /// Apply `delta` to a `stat` value of `val`, as per the rules.
+    pub fn apply(&self, stat: StatBase, val: i32, delta: i32) -> i32 {
+        self.range(stat).apply(val, delta)
+    }
+
+    /// Bring `val` within the range of `stat`.
+    pub fn clamp(&self, stat: StatBase, val: i32) -> i32 {
+        self.range(stat).clamp(val)
+    }
+
+    /// Check that `stat` is within its range.
+    pub fn validate(&self, stat: &Stat) -> Result<(), RuleError> {
+        let (base, value) = (stat.stat_base(), stat.value());
+        let range = self.range(base);
+        if !range.contains(value) {
+            return Err(RuleError::OutOfRange { stat: base, value, range })
+        }
+        Ok(())
+    }
+
+    /// Check that all the stats of a `block` are within their ranges.
+    pub fn validate_block(&self, block: &StatBlock) -> Result<(), RuleError> {
+        block.iter().try_for_each(|s| self.validate(s))
+    }
+
+    /// Make this the active rule set of the current thread, until the returned guard is dropped.
+    pub fn activate(self) -> RuleSetGuard {
+        scoped::activate(&ACTIVE, self)
+    }
+
+    /// Get the active rule set of the current thread.
+    pub fn active() -> Rc<RuleSet> {
+        ACTIVE.with(|a| a.active())
+    }
+}
+
+#[cfg(test)]
+mod rules_tests {
+    use crate::modifier::HasModifier;
+
+    use super::*;
+
+    #[test]
+    fn classic_is_the_default() {
+        assert_eq!("classic", RuleSet::active().name());
+        assert_eq!(1, (Stat::Str { val: 10 } - 13).value());
+        assert_eq!(-13, (Stat::Cha { val: 0 } - 13).value());
+        assert_eq!(500, (Stat::Str { val: 0 } + 500).value());
+    }
+
+    #[test]
+    fn guards_nest_and_restore() {
+        let outer = RuleSet::new("outer").with(StatBase::Dex, StatRange::new(None, Some(20)));
+        let inner = RuleSet::new("inner").with(StatBase::Dex, StatRange::new(None, Some(15)));
+        let g1 = outer.activate();
+        {
+            let _g2 = inner.activate();
+            assert_eq!(15, (Stat::Dex { val: 10 } + 10).value());
+        }
+        assert_eq!(20, (Stat::Dex { val: 10 } + 10).value());
+        drop(g1);
+        assert_eq!("classic", RuleSet::active().name());
+    }
+
+    #[test]
+    fn guards_dropped_out_of_order() {
+        let g1 = RuleSet::new("outer").activate();
+        let g2 = RuleSet::new("inner").activate();
+        drop(g1);
+        assert_eq!("inner", RuleSet::active().name());
+        drop(g2);
+        assert_eq!("classic", RuleSet::active().name());
+    }
+
+    #[test]
+    fn soft_caps() {
+        let r = StatRange::new(Some(1), Some(25)).with_soft_cap(18, 2);
+        assert_eq!(17, r.apply(15, 2));
+        // 16 → 18 normally, the remaining 4 count half
+        assert_eq!(20, r.apply(16, 6));
+        assert_eq!(25, r.apply(20, 100));
+        assert_eq!(12, r.apply(20, -8));
+        let _g = RuleSet::classic().with(StatBase::Str, r).activate();
+        let mut s = Stat::Str { val: 18 };
+        s += 4;
+        assert_eq!(20, s.value());
+    }
+
+    #[test]
+    fn modifier_curves() {
+        let flat = RuleSet::from_json(r#"{
+            "curves": { "Str": { "stepped": { "base": 0, "per": 5 } }, "Cha": "none" }
+        }"#).unwrap();
+        assert_eq!(2, flat.modifier(StatBase::Str, 12));
+        assert_eq!(-1, flat.modifier(StatBase::Str, -1));
+        assert_eq!(0, flat.modifier(StatBase::Cha, 7));
+        // not given → classic
+        assert_eq!(3, flat.modifier(StatBase::Dex, 16));
+        let _g = flat.activate();
+        assert_eq!(2, Stat::Str { val: 12 }.modifier());
+        let huge = ModifierCurve::Stepped { base: -10, per: NonZeroU32::MIN };
+        assert_eq!(i32::MAX, huge.modifier(i32::MAX));
+    }
+
+    #[test]
+    fn racial_maxima() {
+        let rules = RuleSet::classic()
+            .with(StatBase::Dex, StatRange::new(Some(3), Some(18)))
+            .with_racial_max("dwarf", StatBase::Dex, 17)
+            .with_racial_max("dwarf", StatBase::Cha, 16);
+        let dwarf = rules.for_race("dwarf").unwrap();
+        assert_eq!(Some(17), dwarf.range(StatBase::Dex).max);
+        assert_eq!(Some(16), dwarf.range(StatBase::Cha).max);
+        assert!(matches!(dwarf.validate(&Stat::Dex { val: 18 }), Err(RuleError::OutOfRange { .. })));
+        assert!(rules.validate(&Stat::Dex { val: 18 }).is_ok());
+        assert!(matches!(rules.for_race("elf"), Err(RuleError::UnknownRace(_))));
+    }
+
+    #[test]
+    fn json_rules() {
+        let rules = RuleSet::from_json(r#"{
+            "name": "d20ish",
+            "stats": { "Str": { "min": 3, "max": 18, "soft_cap": { "at": 16, "divisor": 2 } } },
+            "races": { "orc": { "Int": 14 } }
+        }"#).unwrap();
+        assert_eq!(Some(3), rules.range(StatBase::Str).min);
+        let mut block = StatBlock::new();
+        block.set(Stat::Str { val: 500 });
+        assert!(rules.validate_block(&block).is_err());
+        assert!(RuleSet::classic().validate_block(&block).is_ok());
+        let zero = r#"{ "stats": { "Str": { "soft_cap": { "at": 16, "divisor": 0 } } } }"#;
+        assert!(matches!(RuleSet::from_json(zero), Err(RuleError::Json(_))));
+        let inverted = r#"{ "stats": { "Str": { "min": 18, "max": 3 } } }"#;
+        assert!(matches!(RuleSet::from_json(inverted), Err(RuleError::Json(_))));
+    }
+}
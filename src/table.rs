@@ -0,0 +1,598 @@
+//! Numbered roll tables, e.g. "T867: Body Locations".
+//!
+//! A [RollTable] has an id/number, a [dice expression][DiceExpr] and a bunch of ranged
+//! [entries][TableEntry]. Each entry resolves into one of:
+//!
+//! **a)** a plain [value][TableResult::Value],
+//! **b)** a nested [sub-table][TableResult::Table] roll,
+//! **c)** a [reference][TableResult::Ref] to another table in a [TableSet], or
+//! **d)** [roll again][TableResult::RollAgain] on the very same table.
+//!
+//! ```
+//! use rpgassist::{dice::Dice, table::RollTable};
+//!
+//! let t = RollTable::new(1, Dice::new(1, 6))
+//!     .with_value(1..=3, "low")
+//!     .with_table(4..=5, RollTable::new(2, Dice::new(1, 2))
+//!         .with_value(1..=1, "mid")
+//!         .with_value(2..=2, "mid-high"))
+//!     .with_value(6..=6, "high");
+//! let _ = t.roll();
+//! ```
+//!
+//! # JSON
+//!
+//! Tables can be loaded from JSON with [RollTable::from_json] or, when they
+//! [reference][TableResult::Ref] each other, as a whole [TableSet]:
+//!
+//! ```json
+//! {
+//!     "id": 1, "name": "Weather", "die": "1d6",
+//!     "entries": [
+//!         { "roll": "1-4", "result": { "value": "Clear" } },
+//!         { "roll": 5, "result": { "ref": 2 } },
+//!         { "roll": [6], "result": "again" }
+//!     ]
+//! }
+//! ```
+use std::{fmt::Display, ops::RangeInclusive, path::Path};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{analysis::Odds, audit::RollRecord, dice::{Dice, DiceExpr}, id::HasId, rng::{ChaosRng, DiceRng}, serialize::{serial_range, serial_strings::deserialize_strings_to_vec}};
+
+mod error;
+pub use error::TableError;
+mod set;
+pub use set::TableSet;
+
+/// How many times [TableResult::RollAgain] may chain before we give up.
+const MAX_REROLLS: usize = 100;
+
+/// What a [TableEntry] resolves into.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum TableResult<T> {
+    /// A plain value.
+    Value(T),
+    /// Roll on a nested sub-table.
+    Table(Box<RollTable<T>>),
+    /// Roll on another table (by id) of the same [TableSet].
+    Ref(usize),
+    /// Ignore this entry and roll again on the same table.
+    #[serde(rename = "again")]
+    RollAgain,
+}
+
+/// A single ranged entry within a [RollTable].
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TableEntry<T> {
+    #[serde(rename = "roll", with = "serial_range")]
+    pub range: RangeInclusive<i32>,
+    pub result: TableResult<T>,
+}
+
+/// A numbered roll table.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RollTable<T> {
+    id: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_strings_to_vec", skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<String>,
+    die: DiceExpr,
+    entries: Vec<TableEntry<T>>,
+}
+
+impl<T> RollTable<T> {
+    /// Make a new, empty, table.
+    pub fn new<D: Into<DiceExpr>>(id: usize, die: D) -> Self {
+        Self { id, name: None, notes: vec![], die: die.into(), entries: vec![] }
+    }
+
+    /// Give the table a name.
+    pub fn named(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Add an entry.
+    pub fn with(mut self, range: RangeInclusive<i32>, result: TableResult<T>) -> Self {
+        self.entries.push(TableEntry { range, result });
+        self
+    }
+
+    /// Add a [plain value][TableResult::Value] entry.
+    pub fn with_value(self, range: RangeInclusive<i32>, value: T) -> Self {
+        self.with(range, TableResult::Value(value))
+    }
+
+    /// Add a [sub-table][TableResult::Table] entry.
+    pub fn with_table(self, range: RangeInclusive<i32>, table: RollTable<T>) -> Self {
+        self.with(range, TableResult::Table(Box::new(table)))
+    }
+
+    /// Add a [roll again][TableResult::RollAgain] entry.
+    pub fn with_roll_again(self, range: RangeInclusive<i32>) -> Self {
+        self.with(range, TableResult::RollAgain)
+    }
+
+    /// Get the table's name, if any.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Get the table's notes, if any.
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+
+    /// Get the [DiceExpr] of the table.
+    pub fn die(&self) -> &DiceExpr {
+        &self.die
+    }
+
+    /// Get the table entries.
+    pub fn entries(&self) -> &[TableEntry<T>] {
+        &self.entries
+    }
+
+    /// Find the entry matching the given `roll`, if any.
+    pub fn entry_for(&self, roll: i32) -> Option<&TableEntry<T>> {
+        self.entries.iter().find(|e| e.range.contains(&roll))
+    }
+
+    /// Range width of each entry (as 'weight') and the entry itself.
+    fn weighted_entries(&self) -> impl Iterator<Item = (u64, &TableEntry<T>)> {
+        self.entries.iter().map(|e| ((*e.range.end() as i64 - *e.range.start() as i64 + 1).max(0) as u64, e))
+    }
+
+    /// Validate the table (and its sub-tables): every possible roll of the
+    /// [DiceExpr] has to hit exactly one entry, and there has to be at least one
+    /// entry which isn't [roll again][TableResult::RollAgain].
+    ///
+    /// [References][TableResult::Ref] are reported as unresolved - see
+    /// [TableSet] for those.
+    pub fn validate(&self) -> Result<(), TableError> {
+        let mut ranges: Vec<&RangeInclusive<i32>> = self.entries.iter().map(|e| &e.range).collect();
+        if let Some(r) = ranges.iter().find(|r| r.is_empty()) {
+            return Err(TableError::EmptyRange { table: self.id, range: (*r).clone() })
+        }
+        ranges.sort_by_key(|r| *r.start());
+
+        let (Some(min), Some(max)) = (self.die.min(), self.die.max()) else {
+            return Err(TableError::Unbounded { table: self.id })
+        };
+        let mut next = min;
+        for r in ranges {
+            if *r.start() < next && *r.start() >= min {
+                return Err(TableError::Overlap { table: self.id, range: r.clone() })
+            }
+            if *r.start() < min || *r.end() > max {
+                return Err(TableError::OutOfRange { table: self.id, range: r.clone() })
+            }
+            if *r.start() > next {
+                return Err(TableError::Gap { table: self.id, range: next..=*r.start() - 1 })
+            }
+            next = *r.end() + 1;
+        }
+        if next <= max {
+            return Err(TableError::Gap { table: self.id, range: next..=max })
+        }
+
+        if self.entries.iter().all(|e| matches!(e.result, TableResult::RollAgain)) {
+            return Err(TableError::NoResults { table: self.id })
+        }
+
+        for e in &self.entries {
+            match &e.result {
+                TableResult::Table(t) => t.validate()?,
+                TableResult::Ref(target) => return Err(TableError::UnresolvedRef { table: self.id, target: *target }),
+                _ => ()
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: Clone> RollTable<T> {
+    /// Make a copy of the table with only the values matching `pred`
+    /// (and roll-again entries) left in. Odds of the remaining values stay
+    /// relative to each other, sub-tables included.
+    ///
+    /// # Returns
+    /// **a)** the filtered table, rolled with a single `1dX`, or
+    /// **b)** `None` if nothing was left, or the table (or a sub-table)
+    /// isn't rolled with a single plain die to begin with.
+    pub fn retain<F: Fn(&T) -> bool>(&self, pred: F) -> Option<Self> {
+        self.reweight(|v| pred(v) as u32)
+    }
+
+    /// Make a copy of the table with each value's odds multiplied by `weight`
+    /// (zero drops the value altogether), sub-tables included. Roll-again
+    /// entries are left as they are.
+    ///
+    /// # Returns
+    /// **a)** the reweighted table, rolled with a single `1dX`, or
+    /// **b)** `None` if nothing was left, or the table (or a sub-table)
+    /// isn't rolled with a single plain die to begin with.
+    pub fn reweight<F: Fn(&T) -> u32>(&self, weight: F) -> Option<Self> {
+        self.reweight_ref(&weight)?.0
+    }
+
+    /// See [RollTable::reweight]; `None` if the table isn't flat, otherwise the
+    /// reweighted table (if anything's left) and how much of the original odds
+    /// it kept, as a `(kept, of)` fraction - roll-again entries don't count
+    /// towards either, as they merely roll again.
+    fn reweight_ref<F: Fn(&T) -> u32>(&self, weight: &F) -> Option<(Option<Self>, u64, u64)> {
+        let sides = self.die.single_die()? as u64;
+        // reweight sub-tables first, they decide how much to scale the weights…
+        // (weight, result, sub-table's kept fraction)
+        let mut kept = vec![];
+        let mut again = 0;
+        let mut scale = 1;
+        for (w, e) in self.weighted_entries() {
+            match &e.result {
+                TableResult::Value(v) => match weight(v) {
+                    0 => (),
+                    m => kept.push((w.checked_mul(m as u64)?, e.result.clone(), None)),
+                },
+                TableResult::Table(t) => {
+                    let (sub, left, of) = t.reweight_ref(weight)?;
+                    let Some(sub) = sub else { continue };
+                    scale = lcm(scale, of);
+                    kept.push((w, TableResult::Table(Box::new(sub)), Some((left, of))));
+                },
+                TableResult::RollAgain => {
+                    again += w;
+                    kept.push((w, TableResult::RollAgain, None));
+                },
+                r => kept.push((w, r.clone(), None::<(u64, u64)>)),
+            }
+        }
+        if kept.iter().all(|(_, r, _)| matches!(r, TableResult::RollAgain)) {
+            return Some((None, 0, 1))
+        }
+
+        let mut entries = vec![];
+        let mut next = 1i32;
+        let mut left = 0u64;
+        for (w, result, sub) in kept {
+            let w = match sub {
+                Some((left, of)) => w.checked_mul(scale / of)?.checked_mul(left)?,
+                None => w.checked_mul(scale)?,
+            };
+            if w == 0 { continue }
+            if !matches!(result, TableResult::RollAgain) {
+                left = left.checked_add(w)?;
+            }
+            let w = i32::try_from(w).ok()?;
+            entries.push(TableEntry { range: next..=next.checked_add(w - 1)?, result });
+            next += w;
+        }
+        let mut table = Self::new(self.id, Dice::new(1, (next - 1) as u32));
+        table.name = self.name.clone();
+        table.notes = self.notes.clone();
+        table.entries = entries;
+        Some((Some(table), left, (sides - again).checked_mul(scale)?))
+    }
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    fn gcd(a: u64, b: u64) -> u64 { if b == 0 { a } else { gcd(b, a % b) } }
+    a / gcd(a, b) * b
+}
+
+impl<T: DeserializeOwned> RollTable<T> {
+    /// Load a single (self-contained) table from JSON and
+    /// [validate][RollTable::validate] it.
+    pub fn from_json(json: &str) -> Result<Self, TableError> {
+        let table: Self = serde_json::from_str(json)?;
+        table.validate()?;
+        Ok(table)
+    }
+
+    /// Load a single (self-contained) table from a JSON file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, TableError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+}
+
+impl<T: Clone> RollTable<T> {
+    /// Roll on the table.
+    ///
+    /// # Panic
+    /// Panics if the roll doesn't match any entry, hits an unresolved
+    /// [reference][TableResult::Ref], or if [roll again][TableResult::RollAgain]
+    /// keeps on chaining (way) too long. A [validated][RollTable::validate]
+    /// table won't do the former two.
+    pub fn roll(&self) -> T {
+        self.roll_with(&mut ChaosRng)
+    }
+
+    /// Roll on the table using the given `rng`.
+    ///
+    /// Audit records of values only tell the roll; see
+    /// [RollTable::roll_recorded_with] for records with the values spelled out.
+    ///
+    /// # Panic
+    /// See [RollTable::roll].
+    pub fn roll_with<R: DiceRng + ?Sized>(&self, rng: &mut R) -> T {
+        self.roll_describing(rng, &|roll, _| roll.to_string())
+    }
+
+    /// Roll on the table, `describe`-ing rolled values for audit records.
+    fn roll_describing<R: DiceRng + ?Sized>(&self, rng: &mut R, describe: &dyn Fn(i32, &T) -> String) -> T {
+        for _ in 0..MAX_REROLLS {
+            let dice = self.die.roll_with(rng);
+            let roll = dice.total();
+            let Some(entry) = self.entry_for(roll) else {
+                panic!("{self}: no entry for roll '{roll}'!")
+            };
+            if rng.is_recording() {
+                let outcome = match &entry.result {
+                    TableResult::Value(v) => describe(roll, v),
+                    TableResult::Table(t) => format!("{roll}: see {t}"),
+                    TableResult::Ref(target) => format!("{roll}: see T{target}"),
+                    TableResult::RollAgain => format!("{roll}: roll again"),
+                };
+                rng.record(RollRecord::new(self, &self.die, dice.raw(), outcome));
+            }
+            match &entry.result {
+                TableResult::Value(v) => return v.clone(),
+                TableResult::Table(t) => return t.roll_describing(rng, describe),
+                TableResult::Ref(target) => panic!("{self}: unresolved reference to T{target}!"),
+                TableResult::RollAgain => continue,
+            }
+        }
+        panic!("{self}: gave up after {MAX_REROLLS} consecutive re-rolls!")
+    }
+}
+
+impl<T: Clone + Display> RollTable<T> {
+    /// Roll on the table using the given `rng`, with the rolled values
+    /// spelled out in audit records.
+    ///
+    /// # Panic
+    /// See [RollTable::roll].
+    pub fn roll_recorded_with<R: DiceRng + ?Sized>(&self, rng: &mut R) -> T {
+        self.roll_describing(rng, &|_, v| v.to_string())
+    }
+}
+
+impl<T: Clone + PartialEq> RollTable<T> {
+    /// Get the exact [Odds] of each possible outcome of the table.
+    ///
+    /// [Roll again][TableResult::RollAgain] entries are accounted for by
+    /// spreading their share over the rest of the entries.
+    ///
+    /// # Panic
+    /// Panics if the table has unresolved [references][TableResult::Ref], or
+    /// if its dice are [too costly][crate::analysis::MAX_EXACT_WORK] to work out exactly.
+    pub fn odds(&self) -> Odds<T> {
+        let dist = self.die.distribution().unwrap_or_else(|e| panic!("{self}: {e}"));
+        let mut odds = Odds::new();
+        let mut again = 0.0;
+        for e in &self.entries {
+            let p: f64 = dist.outcomes()
+                .filter(|(v, _)| e.range.contains(v))
+                .map(|(_, p)| p).sum();
+            match &e.result {
+                TableResult::Value(v) => odds.add(v.clone(), p),
+                TableResult::Table(t) => for (v, q) in t.odds().outcomes() {
+                    odds.add(v.clone(), p * q)
+                },
+                TableResult::Ref(target) => panic!("{self}: unresolved reference to T{target}!"),
+                TableResult::RollAgain => again += p,
+            }
+        }
+        if again > 0.0 && again < 1.0 {
+            odds.scale(1.0 / (1.0 - again));
+        }
+        odds
+    }
+}
+
+impl<T> HasId for RollTable<T> {
+    fn id(&self) -> usize {
+        self.id
+    }
+}
+
+impl<T> Display for RollTable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "T{}", self.id)
+    }
+}
+
+#[cfg(test)]
+mod table_tests {
+    use crate::{dice::Dice, rng::SeededRng};
+
+    use super::*;
+
+    #[test]
+    fn roll_again_never_returns_again() {
+        let t = RollTable::new(1, Dice::new(1, 4))
+            .with_roll_again(1..=3)
+            .with_value(4..=4, 4);
+        let mut rng = SeededRng::new(1);
+        for _ in 0..100 {
+            assert_eq!(4, t.roll_with(&mut rng));
+        }
+    }
+
+    #[test]
+    fn subtable_gets_rolled() {
+        let t = RollTable::new(1, Dice::new(1, 2))
+            .with_value(1..=1, 1)
+            .with_table(2..=2, RollTable::new(2, Dice::new(1, 2))
+                .with_value(1..=2, 2));
+        let mut rng = SeededRng::new(2);
+        let rolls: Vec<i32> = (0..100).map(|_| t.roll_with(&mut rng)).collect();
+        assert!(rolls.contains(&1));
+        assert!(rolls.contains(&2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn uncovered_roll_panics() {
+        let t = RollTable::new(1, Dice::new(1, 2)).with_value(1..=1, 1);
+        let mut rng = SeededRng::new(3);
+        for _ in 0..100 {
+            t.roll_with(&mut rng);
+        }
+    }
+
+    #[test]
+    fn odds_spread_roll_again() {
+        let t = RollTable::new(1, Dice::new(1, 4))
+            .with_value(1..=1, 1)
+            .with_value(2..=3, 2)
+            .with_roll_again(4..=4);
+        let odds = t.odds();
+        assert!((odds.probability(&1) - 1.0 / 3.0).abs() < 1e-9);
+        assert!((odds.probability(&2) - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn retain_keeps_relative_odds() {
+        let t = RollTable::new(1, Dice::new(1, 6))
+            .with_value(1..=1, 1)
+            .with_value(2..=3, 2)
+            .with_table(4..=6, RollTable::new(2, Dice::new(1, 4))
+                .with_value(1..=1, 1)
+                .with_value(2..=2, 3)
+                .with_value(3..=4, 4));
+        let odds = t.retain(|v| *v != 4).unwrap().odds();
+        // originally: 1 = 1/6+1/8, 2 = 1/3, 3 = 1/8, out of 3/4 total…
+        assert!((odds.probability(&1) - (7.0 / 24.0) / (3.0 / 4.0)).abs() < 1e-9);
+        assert!((odds.probability(&2) - (8.0 / 24.0) / (3.0 / 4.0)).abs() < 1e-9);
+        assert!((odds.probability(&3) - (3.0 / 24.0) / (3.0 / 4.0)).abs() < 1e-9);
+        assert!(t.retain(|v| *v > 10).is_none());
+        let curve = RollTable::new(1, "2d6".parse::<DiceExpr>().unwrap()).with_value(2..=12, 1);
+        assert!(curve.retain(|_| true).is_none());
+    }
+
+    #[test]
+    fn reweight_multiplies_odds() {
+        let t = RollTable::new(1, Dice::new(1, 4))
+            .with_value(1..=2, 'a')
+            .with_value(3..=3, 'b')
+            .with_table(4..=4, RollTable::new(2, Dice::new(1, 2))
+                .with_value(1..=1, 'a')
+                .with_value(2..=2, 'c'));
+        let odds = t.reweight(|v| if *v == 'c' { 3 } else { 1 }).unwrap().odds();
+        // a = 1/2 + 1/8, b = 1/4, c = 3/8, out of 5/4 total…
+        assert!((odds.probability(&'a') - 0.5).abs() < 1e-9);
+        assert!((odds.probability(&'b') - 0.2).abs() < 1e-9);
+        assert!((odds.probability(&'c') - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn wide_ranges_weigh_right() {
+        let t = RollTable::new(1, Dice::new(1, 2)).with_value(i32::MIN..=i32::MAX, 1);
+        assert_eq!(vec![1u64 << 32], t.weighted_entries().map(|(w, _)| w).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn retain_nested_tables() {
+        let t = RollTable::new(1, Dice::new(1, 2))
+            .with_value(1..=1, 'x')
+            .with_table(2..=2, RollTable::new(2, Dice::new(1, 2))
+                .with_value(1..=1, 'a')
+                .with_table(2..=2, RollTable::new(3, Dice::new(1, 2))
+                    .with_value(1..=2, 'b')));
+        let odds = t.retain(|_| true).unwrap().odds();
+        assert!((odds.probability(&'x') - 0.5).abs() < 1e-9);
+        assert!((odds.probability(&'a') - 0.25).abs() < 1e-9);
+        assert!((odds.probability(&'b') - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn retain_roll_again_in_sub_table() {
+        let t = RollTable::new(1, Dice::new(1, 2))
+            .with_value(1..=1, 'x')
+            .with_table(2..=2, RollTable::new(2, Dice::new(1, 3))
+                .with_value(1..=1, 'a')
+                .with_value(2..=2, 'b')
+                .with_roll_again(3..=3));
+        // originally x = 1/2, a = 1/4, b = 1/4
+        let odds = t.retain(|v| *v != 'b').unwrap().odds();
+        assert!((odds.probability(&'x') - 2.0 / 3.0).abs() < 1e-9);
+        assert!((odds.probability(&'a') - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn load_from_json() {
+        let t: RollTable<String> = RollTable::from_json(r#"{
+            "id": 5, "name": "Test", "notes": "Just a test.", "die": "2d4",
+            "entries": [
+                { "roll": "2-4", "result": { "value": "low" } },
+                { "roll": [5, 7], "result": { "table": {
+                    "id": 6, "die": "d2",
+                    "entries": [{ "roll": [1, 2], "result": { "value": "mid" } }]
+                } } },
+                { "roll": 8, "result": "again" }
+            ]}"#).unwrap();
+        assert_eq!(5, t.id());
+        assert_eq!(Some("Test"), t.name());
+        assert_eq!(vec!["Just a test.".to_string()], t.notes());
+        assert_eq!(3, t.entries().len());
+    }
+
+    #[test]
+    fn validation_catches_gaps() {
+        let t = RollTable::new(1, Dice::new(1, 6))
+            .with_value(1..=2, 1)
+            .with_value(5..=6, 2);
+        assert!(matches!(t.validate(), Err(TableError::Gap { range, .. }) if range == (3..=4)));
+        let t = RollTable::new(1, Dice::new(1, 6)).with_value(1..=5, 1);
+        assert!(matches!(t.validate(), Err(TableError::Gap { range, .. }) if range == (6..=6)));
+    }
+
+    #[test]
+    fn validation_catches_overlaps() {
+        let t = RollTable::new(1, Dice::new(1, 6))
+            .with_value(1..=4, 1)
+            .with_value(4..=6, 2);
+        assert!(matches!(t.validate(), Err(TableError::Overlap { .. })));
+    }
+
+    #[test]
+    fn validation_catches_out_of_range() {
+        let t = RollTable::new(1, Dice::new(1, 6)).with_value(1..=7, 1);
+        assert!(matches!(t.validate(), Err(TableError::OutOfRange { .. })));
+    }
+
+    #[test]
+    fn validation_catches_broken_subtables() {
+        let t = RollTable::new(1, Dice::new(1, 2))
+            .with_value(1..=1, 1)
+            .with_table(2..=2, RollTable::new(2, Dice::new(1, 3)).with_value(1..=2, 2));
+        assert!(matches!(t.validate(), Err(TableError::Gap { table: 2, .. })));
+    }
+
+    #[test]
+    fn validation_catches_unbounded_dice() {
+        let t = RollTable::new(1, "1d6!".parse::<DiceExpr>().unwrap()).with_value(1..=6, 1);
+        assert!(matches!(t.validate(), Err(TableError::Unbounded { .. })));
+    }
+
+    #[test]
+    fn validation_catches_only_roll_again() {
+        let t: RollTable<i32> = RollTable::new(1, Dice::new(1, 2)).with_roll_again(1..=2);
+        assert!(matches!(t.validate(), Err(TableError::NoResults { .. })));
+    }
+
+    #[test]
+    fn non_display_values_roll() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Loot(u32);
+        let t = RollTable::new(1, Dice::new(1, 2)).with_value(1..=2, Loot(5));
+        let mut rng = crate::audit::AuditedRng::new(SeededRng::new(1));
+        assert_eq!(Loot(5), t.roll_with(&mut rng));
+        let rec = &rng.records()[0];
+        assert_eq!(rec.raw[0].to_string(), rec.outcome);
+    }
+}
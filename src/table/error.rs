@@ -0,0 +1,69 @@
+//! Errors from loading and validating [RollTable][crate::table::RollTable]s.
+use std::{fmt::Display, ops::RangeInclusive};
+
+/// Something wrong with a table (or loading it).
+#[derive(Debug)]
+pub enum TableError {
+    /// Reading the table data failed.
+    Io(std::io::Error),
+    /// JSON didn't parse as a table.
+    Json(serde_json::Error),
+    /// Range with `start` > `end`.
+    EmptyRange { table: usize, range: RangeInclusive<i32> },
+    /// Range overlaps with some other range.
+    Overlap { table: usize, range: RangeInclusive<i32> },
+    /// Range falls (partially) outside what the table's dice can roll.
+    OutOfRange { table: usize, range: RangeInclusive<i32> },
+    /// Roll(s) not covered by any entry.
+    Gap { table: usize, range: RangeInclusive<i32> },
+    /// Table dice without an upper (or lower) bound, e.g. exploding ones.
+    Unbounded { table: usize },
+    /// Nothing but "roll again" entries.
+    NoResults { table: usize },
+    /// Reference to a table which doesn't exist (in the set).
+    UnresolvedRef { table: usize, target: usize },
+    /// Tables reference each other in a loop.
+    Cycle { table: usize },
+    /// Two or more tables with the same id in a set.
+    DuplicateId(usize),
+}
+
+impl Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Cannot read table data: {e}"),
+            Self::Json(e) => write!(f, "Cannot parse table data: {e}"),
+            Self::EmptyRange { table, range } => write!(f, "T{table}: empty range {}-{}", range.start(), range.end()),
+            Self::Overlap { table, range } => write!(f, "T{table}: range {}-{} overlaps with another", range.start(), range.end()),
+            Self::OutOfRange { table, range } => write!(f, "T{table}: range {}-{} is out of dice range", range.start(), range.end()),
+            Self::Gap { table, range } => write!(f, "T{table}: no entry for {}-{}", range.start(), range.end()),
+            Self::Unbounded { table } => write!(f, "T{table}: dice have no fixed min/max"),
+            Self::NoResults { table } => write!(f, "T{table}: nothing but roll-again entries"),
+            Self::UnresolvedRef { table, target } => write!(f, "T{table}: reference to unknown T{target}"),
+            Self::Cycle { table } => write!(f, "T{table}: tables reference each other in a loop"),
+            Self::DuplicateId(id) => write!(f, "T{id} defined more than once"),
+        }
+    }
+}
+
+impl std::error::Error for TableError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Json(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for TableError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for TableError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
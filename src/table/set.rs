@@ -0,0 +1,150 @@
+//! A set of [RollTable]s which may [reference][TableResult::Ref] each other.
+use std::{collections::BTreeMap, path::Path};
+
+use serde::{Deserialize, de::DeserializeOwned};
+
+use crate::{id::HasId, table::{RollTable, TableError, TableResult}};
+
+/// A bunch of [RollTable]s keyed by their id.
+///
+/// [References][TableResult::Ref] between the tables get resolved (into
+/// [sub-tables][TableResult::Table]) when the set is made.
+#[derive(Debug, Clone)]
+pub struct TableSet<T> {
+    tables: BTreeMap<usize, RollTable<T>>,
+}
+
+impl<T: Clone> TableSet<T> {
+    /// Make a set out of given `tables`, resolve references between them and
+    /// [validate][RollTable::validate] the lot.
+    pub fn new(tables: Vec<RollTable<T>>) -> Result<Self, TableError> {
+        let mut raw = BTreeMap::new();
+        for t in tables {
+            let id = t.id();
+            if raw.insert(id, t).is_some() {
+                return Err(TableError::DuplicateId(id))
+            }
+        }
+
+        let mut tables = BTreeMap::new();
+        for (id, t) in &raw {
+            let t = resolve(t, &raw, &mut vec![*id])?;
+            t.validate()?;
+            tables.insert(*id, t);
+        }
+        Ok(Self { tables })
+    }
+
+    /// Get a table by its `id`.
+    pub fn get(&self, id: usize) -> Option<&RollTable<T>> {
+        self.tables.get(&id)
+    }
+
+    /// Iterate through the tables in id order.
+    pub fn iter(&self) -> impl Iterator<Item = &RollTable<T>> {
+        self.tables.values()
+    }
+}
+
+impl<T: Clone + DeserializeOwned> TableSet<T> {
+    /// Load a set from JSON - either a single table or an array of them.
+    pub fn from_json(json: &str) -> Result<Self, TableError> {
+        #[derive(Deserialize)]
+        #[serde(untagged, bound = "T: DeserializeOwned")]
+        enum TablesHalp<T> {
+            S(RollTable<T>),
+            M(Vec<RollTable<T>>),
+        }
+
+        match serde_json::from_str(json)? {
+            TablesHalp::S(t) => Self::new(vec![t]),
+            TablesHalp::M(v) => Self::new(v),
+        }
+    }
+
+    /// Load a set from a JSON file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, TableError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// Replace [references][TableResult::Ref] in `table` with copies of the tables they point at.
+fn resolve<T: Clone>(table: &RollTable<T>, raw: &BTreeMap<usize, RollTable<T>>, stack: &mut Vec<usize>) -> Result<RollTable<T>, TableError> {
+    let mut resolved = table.clone();
+    for e in resolved.entries.iter_mut() {
+        match &e.result {
+            TableResult::Ref(target) => {
+                let Some(t) = raw.get(target) else {
+                    return Err(TableError::UnresolvedRef { table: table.id(), target: *target })
+                };
+                if stack.contains(target) {
+                    return Err(TableError::Cycle { table: *target })
+                }
+                stack.push(*target);
+                e.result = TableResult::Table(Box::new(resolve(t, raw, stack)?));
+                stack.pop();
+            },
+            TableResult::Table(t) => e.result = TableResult::Table(Box::new(resolve(t, raw, stack)?)),
+            _ => ()
+        }
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod table_set_tests {
+    use crate::rng::SeededRng;
+
+    use super::*;
+
+    #[test]
+    fn refs_get_resolved() {
+        let set: TableSet<String> = TableSet::from_json(r#"[
+            { "id": 1, "die": "1d2", "entries": [
+                { "roll": 1, "result": { "value": "one" } },
+                { "roll": 2, "result": { "ref": 2 } }
+            ]},
+            { "id": 2, "die": "1d2", "entries": [
+                { "roll": "1-2", "result": { "value": "two" } }
+            ]}
+        ]"#).unwrap();
+        let t = set.get(1).unwrap();
+        let mut rng = SeededRng::new(4);
+        let rolls: Vec<String> = (0..50).map(|_| t.roll_with(&mut rng)).collect();
+        assert!(rolls.contains(&"two".to_string()));
+    }
+
+    #[test]
+    fn missing_ref_is_an_error() {
+        let set: Result<TableSet<String>, _> = TableSet::from_json(r#"
+            { "id": 1, "die": "1d2", "entries": [
+                { "roll": 1, "result": { "value": "one" } },
+                { "roll": 2, "result": { "ref": 2 } }
+            ]}"#);
+        assert!(matches!(set, Err(TableError::UnresolvedRef { table: 1, target: 2 })));
+    }
+
+    #[test]
+    fn cycles_are_errors() {
+        let set: Result<TableSet<String>, _> = TableSet::from_json(r#"[
+            { "id": 1, "die": "1d2", "entries": [
+                { "roll": 1, "result": { "value": "one" } },
+                { "roll": 2, "result": { "ref": 2 } }
+            ]},
+            { "id": 2, "die": "1d2", "entries": [
+                { "roll": 1, "result": { "value": "two" } },
+                { "roll": 2, "result": { "ref": 1 } }
+            ]}
+        ]"#);
+        assert!(matches!(set, Err(TableError::Cycle { .. })));
+    }
+
+    #[test]
+    fn duplicate_ids_are_errors() {
+        let set: Result<TableSet<String>, _> = TableSet::from_json(r#"[
+            { "id": 1, "die": "1d1", "entries": [{ "roll": 1, "result": { "value": "one" } }]},
+            { "id": 1, "die": "1d1", "entries": [{ "roll": 1, "result": { "value": "uno" } }]}
+        ]"#);
+        assert!(matches!(set, Err(TableError::DuplicateId(1))));
+    }
+}